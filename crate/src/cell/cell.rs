@@ -1,21 +1,27 @@
 use std::fmt::{self, Display};
-use crate::cell::{SetMethod, SYMBOLS};
-use crate::utils::bit_utils::{highest_bit_position, number_of_bits_set, power_of_2_bit_positions};
+use crate::cell::{SetMethod, SYMBOLS, CellState, JsonCell, JsonCellRow, JsonCellColumn};
+use crate::error::SolverError;
+use crate::utils::bit_utils::{OptionBits, highest_bit_position, number_of_bits_set, power_of_2_bit_positions};
+
+// Cell of a grid with up to 64 symbols - see CellOf for the option bits of smaller or larger grids
+pub type Cell = CellOf<u64>;
 
 #[derive(Debug, Copy, Clone)]
-pub struct Cell {
+pub struct CellOf<T: OptionBits> {
   max_cells: usize,
   max_columns: usize,
 
   pub column: usize,
   pub row: usize,
-  pub options: u64,
+  pub options: T,
   pub total_options_remaining: usize,
 
-  pub set_method: SetMethod
+  pub set_method: SetMethod,
+  pub pencil_marks: T,                                              // User's own candidates, not used by the solver
+  pub highlights: T
 }
 
-impl Display for Cell {
+impl<T: OptionBits> Display for CellOf<T> {
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
     if formatter.alternate() {
       write!(formatter, "{:0>1$b}", self.options, (self.max_cells + 1) * self.max_columns - 1)
@@ -25,27 +31,31 @@ impl Display for Cell {
   }
 }
 
-impl Cell {
+impl<T: OptionBits> CellOf<T> {
   pub fn new(max_columns: usize, max_rows: usize, column: usize, row: usize) -> Self {
     let max_cells = max_columns * max_rows;
-    Cell {
+    Self {
       max_cells,
       max_columns,
       column,
       row,
-      options: (1 << max_cells) - 1,                                // Set all bits
+      options: T::mask(max_cells),                                  // Set all bits
       total_options_remaining: max_cells,
-      set_method: SetMethod::Unset
+      set_method: SetMethod::Unset,
+      pencil_marks: T::ZERO,
+      highlights: T::ZERO
     }
   }
 
   pub fn reset(&mut self) {
     self.set_method = SetMethod::Unset;
     self.total_options_remaining = self.max_cells;
-    self.options = (1 << self.max_cells) - 1; // Set all bits
+    self.options = T::mask(self.max_cells); // Set all bits
+    self.pencil_marks = T::ZERO;
+    self.highlights = T::ZERO;
   }
 
-  pub fn equal(&self, cell: &Cell) -> bool {
+  pub fn equal(&self, cell: &Self) -> bool {
     self.options == cell.options
   }
 
@@ -64,9 +74,9 @@ impl Cell {
   pub fn remove_option_at_position(&mut self, column: usize, row: usize) -> bool {
     // Return if last option left after removing this option
     let mut last_option_found = false;
-    let bit = 1 << self.max_columns * row + column;
+    let bit = T::bit(self.max_columns * row + column);
 
-    if self.options & bit > 0 {
+    if self.options & bit != T::ZERO {
       // Check if option to remove exists
       self.options &= !bit;
       self.total_options_remaining -= 1;
@@ -79,11 +89,11 @@ impl Cell {
     last_option_found
   }
 
-  pub fn remove_option(&mut self, option: u64) -> bool {
+  pub fn remove_option(&mut self, option: T) -> bool {
     // Return if last option left after removing this option
     let mut last_option_found = false;
 
-    if self.options & option > 0 && self.total_options_remaining > 1 {
+    if self.options & option != T::ZERO && self.total_options_remaining > 1 {
       // Check if option to remove exists and not last option
       self.options &= !option;
       self.total_options_remaining -= 1;
@@ -96,11 +106,11 @@ impl Cell {
     last_option_found
   }
 
-  pub fn remove_options(&mut self, remove: u64) -> bool {
+  pub fn remove_options(&mut self, remove: T) -> bool {
     let mut last_option_found = false;
 
     let mut remove_options = self.options & remove;
-    if remove_options > 0 && self.total_options_remaining > 1 && self.options & !remove > 0 {
+    if remove_options != T::ZERO && self.total_options_remaining > 1 && self.options & !remove != T::ZERO {
       // Remove options iff cell contains other options
       self.options -= remove_options;
       self.total_options_remaining -= number_of_bits_set(remove_options);
@@ -109,9 +119,9 @@ impl Cell {
         self.set_method = SetMethod::Calculated;
         last_option_found = true;
       } else {
-        while remove_options > 0 {
+        while remove_options != T::ZERO {
           let highest_bit_pos = highest_bit_position(remove_options);
-          remove_options -= 1 << highest_bit_pos;
+          remove_options -= T::bit(highest_bit_pos);
         }
       }
     }
@@ -129,7 +139,7 @@ impl Cell {
     self.clear_all_except_at_position(index % self.max_columns, index / self.max_columns >> 0);
   }
 
-  pub fn set_by_option(&mut self, option: u64, set_method: SetMethod) {
+  pub fn set_by_option(&mut self, option: T, set_method: SetMethod) {
     self.set_by_index(power_of_2_bit_positions(option), set_method);
   }
 
@@ -137,34 +147,140 @@ impl Cell {
     self.set_by_index(find_symbol_index(symbol), set_method);
   }
 
-  pub fn contains_option(&self, option: u64) -> bool {
-    self.options & option > 0
+  // Options e.g. from a saved game, without striking out - a single option is treated as loaded
+  pub fn load_options(&mut self, options: T) {
+    self.options = options;
+    self.total_options_remaining = number_of_bits_set(options);
+    self.set_method = if self.total_options_remaining == 1 { SetMethod::Loaded } else { SetMethod::Unset };
+  }
+
+  pub fn state(&self) -> CellState<T> {
+    CellState { options: self.options, set_method: self.set_method, pencil_marks: self.pencil_marks }
+  }
+
+  // Restore a state as is e.g. to undo a move
+  pub fn set_state(&mut self, state: &CellState<T>) {
+    self.options = state.options;
+    self.total_options_remaining = number_of_bits_set(state.options);
+    self.set_method = state.set_method;
+    self.pencil_marks = state.pencil_marks;
+  }
+
+  // User's marks only i.e. the options are unchanged
+  pub fn toggle_pencil_mark_at_position(&mut self, column: usize, row: usize) {
+    self.pencil_marks ^= T::bit(row * self.max_columns + column);
+  }
+
+  pub fn toggle_highlight_option_at_position(&mut self, column: usize, row: usize) {
+    self.highlights ^= T::bit(row * self.max_columns + column);
+  }
+
+  // Options marked by the user that are no longer candidates
+  pub fn pencil_mark_mistakes(&self) -> T {
+    self.pencil_marks & !self.options
+  }
+
+  // A set cell's symbol and set method, otherwise each option by row, struck out if removed
+  pub fn to_json(&self) -> JsonCell {
+    let mut json = JsonCell { column: self.column, row: self.row, rows: None, symbol: None, set_method: None };
+
+    if self.solved() {
+      json.symbol = Some(self.symbol());
+      json.set_method = Some(self.set_method);
+    } else {
+      let mut rows = Vec::with_capacity(self.max_cells / self.max_columns);
+      for row in 0..self.max_cells / self.max_columns {
+        let mut columns = Vec::with_capacity(self.max_columns);
+        for column in 0..self.max_columns {
+          let bit = T::bit(row * self.max_columns + column);
+          columns.push(JsonCellColumn {
+            symbol: SYMBOLS[row * self.max_columns + column],
+            strike_out: self.options & bit == T::ZERO,
+            highlight: self.highlights & bit != T::ZERO,
+            pencil_mark: self.pencil_marks & bit != T::ZERO
+          });
+        }
+        rows.push(JsonCellRow { columns });
+      }
+      json.rows = Some(rows);
+    }
+
+    json
+  }
+
+  // Options not struck out, or the symbol set (loaded if no set method) - without striking out other cells
+  pub fn set_json(&mut self, json: &JsonCell) -> Result<(), SolverError<T>> {
+    if let Some(rows) = &json.rows {
+      let mut options = T::ZERO;
+      let mut pencil_marks = T::ZERO;
+      let mut highlights = T::ZERO;
+      for (row, json_row) in rows.iter().enumerate() {
+        for (column, json_column) in json_row.columns.iter().enumerate() {
+          if column >= self.max_columns || row * self.max_columns + column >= self.max_cells {
+            return Err(SolverError::InvalidJson(format!("Option {}, {} outside of cell", column, row)));
+          }
+          let bit = T::bit(row * self.max_columns + column);
+          if !json_column.strike_out {
+            options |= bit;
+          }
+          if json_column.pencil_mark {
+            pencil_marks |= bit;
+          }
+          if json_column.highlight {
+            highlights |= bit;
+          }
+        }
+      }
+
+      if options == T::ZERO {
+        return Err(SolverError::InvalidJson(String::from("Every option of a cell struck out")));
+      }
+      self.load_options(options);
+      self.pencil_marks = pencil_marks;
+      self.highlights = highlights;
+    } else if let Some(symbol) = json.symbol {
+      let set_method = json.set_method.filter(|&x| x != SetMethod::Unset).unwrap_or(SetMethod::Loaded);
+      match SYMBOLS.iter().position(|&x| x == symbol) {
+        Some(index) if index < self.max_cells => self.set_by_index(index, set_method),
+        _ => return Err(SolverError::InvalidSymbol(symbol))
+      }
+      self.pencil_marks = T::ZERO;
+      self.highlights = T::ZERO;
+    } else {
+      return Err(SolverError::InvalidJson(String::from("Cell without options or a symbol")));
+    }
+
+    Ok(())
+  }
+
+  pub fn contains_option(&self, option: T) -> bool {
+    self.options & option != T::ZERO
   }
 
   pub fn contains_option_at_position(&self, column: usize, row: usize) -> bool {
-    let bit = 1 << row * self.max_columns + column;
-    (self.options & bit) > 0
+    let bit = T::bit(row * self.max_columns + column);
+    (self.options & bit) != T::ZERO
   }
 
-  pub fn contains_options(&self, check_options: u64) -> bool {
+  pub fn contains_options(&self, check_options: T) -> bool {
     self.options & check_options == check_options
   }
 
   pub fn contains_symbol(&self, symbol: char) -> bool {
-    self.options & 1 << find_symbol_index(symbol) > 0
+    self.options & T::bit(find_symbol_index(symbol)) != T::ZERO
   }
 
   fn clear_all_except_at_position(&mut self, column: usize, row: usize) {
-    self.options = 1 << self.max_columns * row + column;
+    self.options = T::bit(self.max_columns * row + column);
     self.total_options_remaining = 1;
   }
 
   pub fn removed_options_per_row(&mut self, row: usize) -> Vec<usize> {
     let mut removed_options = Vec::with_capacity(self.max_columns);
 
-    let mut bit = 1 << row * self.max_columns;
+    let mut bit = T::bit(row * self.max_columns);
     for column in 0..self.max_columns {
-      if self.options & bit == 0 {
+      if self.options & bit == T::ZERO {
         removed_options.push(column);
       }
       bit <<= 1;                                                    // bit = 1 << row * columns + column
@@ -38,7 +38,7 @@ mod cell {
 
 #[cfg(test)]
 mod symbol {
-  use crate::cell::{SYMBOLS, cell::Cell, SetMethod};
+  use crate::cell::{SYMBOLS, cell::{Cell, CellOf}, SetMethod};
 
   #[test]
   fn it_sets_by_option_1() {
@@ -128,9 +128,20 @@ mod symbol {
     let max_rows = 6;
     let mut cell = Cell::new(max_columns, max_rows, 0, 0); 
 
+    SYMBOLS.iter().take(max_columns * max_rows).enumerate().for_each(|(i, x)| {
+      cell.set_by_symbol(*x, SetMethod::User);
+      assert_eq!(cell.symbol(), SYMBOLS[i]);
+    });
+  }
+
+  #[test]
+  fn it_set_by_all_symbols_of_a_10x10_grid() {
+    let mut cell = CellOf::<u128>::new(10, 10, 0, 0);
+
     SYMBOLS.iter().enumerate().for_each(|(i, x)| {
       cell.set_by_symbol(*x, SetMethod::User);
       assert_eq!(cell.symbol(), SYMBOLS[i]);
+      assert_eq!(cell.options, 1 << i);
     });
   }
 }
@@ -289,7 +300,7 @@ mod cell_3x3 {
 
     assert_eq!(cell.removed_options_per_row(0), [0, 1, 2]);         // 0 0 0    - all removed from row 0
     assert_eq!(cell.removed_options_per_row(1), [1]);               // 1 0 1    - only 2nd option removed
-    assert_eq!(cell.removed_options_per_row(2), []);                // 1 1 1    - no options removed
+    assert_eq!(cell.removed_options_per_row(2), [0_usize; 0]);      // 1 1 1    - no options removed
   }
 
   #[test]
@@ -358,3 +369,92 @@ mod cell_3x3 {
     assert_eq!(cell.set_method, SetMethod::Calculated);
   }
 }
+
+#[cfg(test)]
+mod cell_json {
+  use crate::cell::{cell::Cell, SetMethod, JsonCell};
+  use crate::error::SolverError;
+
+  #[test]
+  fn it_converts_options_to_json() {
+    let mut cell = Cell::new(2, 1, 1, 0);                           // 1 | 2
+    cell.remove_option(2);
+    cell.remove_option(1);                                          // Last option kept
+
+    let json = cell.to_json();
+    assert_eq!(json.symbol, Some('1'));
+    assert_eq!(json.set_method, Some(SetMethod::Calculated));
+    assert_eq!(json.rows, None);
+
+    let mut cell = Cell::new(3, 2, 1, 0);
+    cell.remove_option(4);
+    let json = cell.to_json();
+    assert_eq!((json.column, json.row), (1, 0));
+    let rows = json.rows.unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].columns.iter().map(|x| x.symbol).collect::<String>(), "456");
+    assert_eq!(rows[0].columns.iter().map(|x| x.strike_out).collect::<Vec<bool>>(), [false, false, true]);
+  }
+
+  #[test]
+  fn it_loads_json() {
+    let mut cell = Cell::new(3, 2, 0, 0);
+    cell.remove_option(4);
+    cell.remove_option(32);
+
+    let mut copy = Cell::new(3, 2, 0, 0);
+    copy.set_json(&cell.to_json()).unwrap();
+    assert_eq!(copy.options, 1 + 2 + 8 + 16);
+    assert_eq!(copy.total_options_remaining, 4);
+    assert_eq!(copy.set_method, SetMethod::Unset);
+
+    cell.set_by_symbol('5', SetMethod::User);
+    copy.set_json(&cell.to_json()).unwrap();
+    assert_eq!(copy.options, 16);
+    assert_eq!(copy.set_method, SetMethod::User);
+
+    let json: JsonCell = serde_json::from_str(r#"{"symbol":"3"}"#).unwrap();
+    copy.set_json(&json).unwrap();
+    assert_eq!(copy.symbol(), '3');
+    assert_eq!(copy.set_method, SetMethod::Loaded);                 // Loaded unless the set method is given
+  }
+
+  #[test]
+  fn it_keeps_pencil_marks_separate_from_options() {
+    let mut cell = Cell::new(3, 2, 0, 0);
+    cell.toggle_pencil_mark_at_position(1, 0);                      // 2
+    cell.toggle_pencil_mark_at_position(2, 1);                      // 6
+    cell.toggle_highlight_option_at_position(2, 1);
+    cell.remove_option(32);
+
+    assert_eq!(cell.pencil_marks, 2 + 32);
+    assert_eq!(cell.highlights, 32);
+    assert_eq!(cell.pencil_mark_mistakes(), 32);
+
+    let json = cell.to_json();
+    let column = &json.rows.as_ref().unwrap()[1].columns[2];
+    assert!(column.strike_out && column.highlight && column.pencil_mark);
+
+    let mut copy = Cell::new(3, 2, 0, 0);
+    copy.set_json(&json).unwrap();
+    assert_eq!((copy.options, copy.pencil_marks, copy.highlights), (31, 34, 32));
+
+    cell.toggle_pencil_mark_at_position(1, 0);
+    cell.reset();
+    assert_eq!((cell.pencil_marks, cell.highlights), (0, 0));
+  }
+
+  #[test]
+  fn it_rejects_invalid_json() {
+    let mut cell = Cell::new(2, 1, 0, 0);
+
+    let json: JsonCell = serde_json::from_str(r#"{"symbol":"3"}"#).unwrap();
+    assert_eq!(cell.set_json(&json), Err(SolverError::InvalidSymbol('3')));
+
+    let json: JsonCell = serde_json::from_str(
+      r#"{"rows":[{"columns":[{"symbol":"1","strikeOut":true},{"symbol":"2","strikeOut":true}]}]}"#
+    ).unwrap();
+    assert!(matches!(cell.set_json(&json), Err(SolverError::InvalidJson(_))));
+    assert_eq!(cell.options, 3);                                    // Unchanged
+  }
+}
@@ -1,20 +1,74 @@
+use wasm_bindgen::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::utils::bit_utils::OptionBits;
+
 pub mod cell;
 
 mod cell_test;
 
-pub const SYMBOLS: [char; 36] = [                                   // Max 6 x 6
-  '1', '2', '3', '4', '5', '6',
-  '7', '8', '9', 'A', 'B', 'C',
-  'D', 'E', 'F', 'G', 'H', 'I',
-  'J', 'K', 'L', 'M', 'N', 'O',
-  'P', 'Q', 'R', 'S', 'T', 'U',
-  'V', 'W', 'X', 'Y', 'Z', '0'
+pub const SYMBOLS: [char; 100] = [                                  // Max 10 x 10, within the grid's option bits
+  '1', '2', '3', '4', '5', '6', '7', '8', '9', 'A',
+  'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K',
+  'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U',
+  'V', 'W', 'X', 'Y', 'Z', '0', 'α', 'β', 'γ', 'δ',                 // Up to 6 x 6 in ASCII, then Greek
+  'ε', 'ζ', 'η', 'θ', 'ι', 'κ', 'λ', 'μ', 'ν', 'ξ',
+  'ο', 'π', 'ρ', 'σ', 'τ', 'υ', 'φ', 'χ', 'ψ', 'ω',
+  'Γ', 'Δ', 'Θ', 'Λ', 'Ξ', 'Π', 'Σ', 'Φ', 'Ψ', 'Ω',                 // Greek capitals unlike Latin ones
+  'À', 'Á', 'Â', 'Ã', 'Ä', 'Å', 'Æ', 'Ç', 'È', 'É',
+  'Ê', 'Ë', 'Ì', 'Í', 'Î', 'Ï', 'Ð', 'Ñ', 'Ò', 'Ó',
+  'Ô', 'Õ', 'Ö', 'Ø', 'Ù', 'Ú', 'Û', 'Ü', 'Ý', 'Þ'
 ];
 
-#[derive(Copy, Clone, Debug, PartialEq)] 
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum SetMethod {
   Unset,
   Loaded,
   User,
   Calculated
 }
+
+// Options, set method and pencil marks of a cell, as recorded by the grid's history
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "T: OptionBits")]
+pub struct CellState<T: OptionBits = u64> {
+  pub options: T,
+  pub set_method: SetMethod,
+  #[serde(default)]
+  pub pencil_marks: T
+}
+
+// Snapshot of a cell as rendered by the app (IJsonCell): a set cell's symbol, otherwise every option struck out or not
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCell {
+  #[serde(default)]
+  pub column: usize,                                                // Coordinates within the sub-grid
+  #[serde(default)]
+  pub row: usize,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub rows: Option<Vec<JsonCellRow>>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub symbol: Option<char>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub set_method: Option<SetMethod>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonCellRow {
+  pub columns: Vec<JsonCellColumn>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCellColumn {
+  pub symbol: char,
+  #[serde(default)]
+  pub strike_out: bool,                                             // Option removed
+  #[serde(default)]
+  pub highlight: bool,
+  #[serde(default)]
+  pub pencil_mark: bool                                             // Noted by the user as a candidate
+}
@@ -0,0 +1,511 @@
+use crate::constraint::{Constraint, Cage, Sandwich};
+use crate::error::SolverError;
+use crate::grid::{grid::GridOf, Position};
+use crate::utils::bit_utils::{OptionBits, number_of_bits_set, highest_bit_position};
+
+const MAX_SANDWICH_COMBINATIONS: usize = 100000;                    // Every sum up to 25 x 25 grids
+
+// Options that can't be part of a solution removed from the constraint's cells, using the combinations worked out when
+//   it was added - true if any option removed
+pub fn apply<T: OptionBits>(grid: &mut GridOf<T>, constraint: &Constraint, combinations: &[Vec<T>]) -> bool {
+  match constraint {
+    Constraint::Cage(cage) => apply_cage(grid, cage, &combinations[cage.positions.len()]),
+    _ => apply_line(grid, constraint, combinations)
+  }
+}
+
+// Cells breaking the constraint, if any
+pub fn violation<T: OptionBits>(grid: &GridOf<T>, constraint: &Constraint, combinations: &[Vec<T>]) -> Option<Vec<Position>> {
+  match constraint {
+    Constraint::Cage(cage) => cage_violation(grid, cage, &combinations[cage.positions.len()]),
+    _ => line_violation(grid, constraint, combinations)
+  }
+}
+
+// Options of each set of values adding up to the constraint's sum, by the number of cells they fill - worked out once
+//   when the constraint is added, as too slow to repeat each time it's applied
+pub fn combinations<T: OptionBits>(grid: &GridOf<T>, constraint: &Constraint) -> Vec<Vec<T>> {
+  match constraint {
+    Constraint::Cage(cage) => {
+      let mut combinations = vec![Vec::new(); cage.positions.len() + 1];
+      combinations[cage.positions.len()] = cage_combinations(grid, cage);
+      combinations
+    },
+    Constraint::Sandwich(sandwich) => sandwich_combinations(grid, sandwich),
+    _ => Vec::new()
+  }
+}
+
+// Constraint's cells within the grid, without repeats
+pub fn validate<T: OptionBits>(grid: &GridOf<T>, constraint: &Constraint) -> Result<(), SolverError<T>> {
+  let positions = constraint.positions();
+  for (index, position) in positions.iter().enumerate() {
+    if !grid.valid_position(position) {
+      return Err(SolverError::InvalidPosition(*position));
+    }
+    if positions[..index].contains(position) {
+      return Err(SolverError::InvalidConstraint(format!("{} repeated in {}", position, constraint)));
+    }
+  }
+
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+  let valid = match constraint {
+    Constraint::Cage(cage) => !cage.positions.is_empty() && cage.positions.len() <= max_options,
+    Constraint::Thermometer(thermometer) => {
+      thermometer.positions.len() >= 2 && thermometer.positions.len() <= max_options && joined(grid, &thermometer.positions)
+    },
+    Constraint::Arrow(arrow) => !arrow.positions.is_empty() && joined(grid, &positions),   // From the circle
+    Constraint::Palindrome(palindrome) => palindrome.positions.len() >= 2 && joined(grid, &palindrome.positions),
+    Constraint::Sandwich(sandwich) => sandwich.positions.len() == max_options && straight(grid, &sandwich.positions, false),
+    Constraint::LittleKiller(little_killer) => {
+      !little_killer.positions.is_empty() && straight(grid, &little_killer.positions, true) && edge_to_edge(grid, &little_killer.positions)
+    }
+  };
+
+  if !valid {
+    return Err(SolverError::InvalidConstraint(format!(
+      "{} too short, too long, not joined up, not in a line or not from edge to edge",
+      constraint
+    )));
+  }
+
+  Ok(())
+}
+
+pub fn to_json(constraints: &[Constraint]) -> String {
+  serde_json::to_string(constraints).expect("Error writing constraints")
+}
+
+pub fn from_json(json: &str) -> Result<Vec<Constraint>, SolverError> {
+  serde_json::from_str(json).map_err(|error| SolverError::InvalidJson(error.to_string()))
+}
+
+fn apply_cage<T: OptionBits>(grid: &mut GridOf<T>, cage: &Cage, combinations: &[T]) -> bool {
+  let mut removed = false;
+
+  // No repeats: options of solved cells removed from the rest of the cage
+  for position in cage.positions.iter() {
+    let cell = grid.get_cell(position);
+    if cell.solved() {
+      let option = cell.options;
+      for other in cage.positions.iter().filter(|&x| x != position) {
+        removed |= remove_options(grid, other, option);
+      }
+    }
+  }
+
+  // Sums: options only kept if in a combination of values adding up to the sum that fits the cells
+  let options: Vec<T> = cage.positions.iter().map(|x| grid.get_cell(x).options).collect();
+  let mut allowed = vec![T::ZERO; options.len()];
+  for &combination in combinations.iter().filter(|&&x| fits(&options, x)) {
+    for (index, &cell_options) in options.iter().enumerate() {
+      allowed[index] |= cell_options & combination;
+    }
+  }
+
+  for (index, position) in cage.positions.iter().enumerate() {
+    if allowed[index] != T::ZERO {                                  // Left for the consistency check if nothing fits
+      removed |= remove_options(grid, position, options[index] & !allowed[index]);
+    }
+  }
+
+  removed
+}
+
+fn cage_violation<T: OptionBits>(grid: &GridOf<T>, cage: &Cage, combinations: &[T]) -> Option<Vec<Position>> {
+  let options: Vec<T> = cage.positions.iter().map(|x| grid.get_cell(x).options).collect();
+
+  let mut set_options = T::ZERO;
+  for (index, &cell_options) in options.iter().enumerate() {
+    if grid.get_cell(&cage.positions[index]).solved() {
+      if set_options & cell_options != T::ZERO {
+        return Some(cage.positions.iter()
+          .filter(|&x| grid.get_cell(x).options == cell_options)
+          .copied()
+          .collect());
+      }
+      set_options |= cell_options;
+    }
+  }
+
+  if !combinations.iter().any(|&x| fits(&options, x)) {
+    return Some(cage.positions.clone());
+  }
+
+  None
+}
+
+// Options not allowed by a thermometer, arrow, palindrome or outside clue removed from its cells
+fn apply_line<T: OptionBits>(grid: &mut GridOf<T>, constraint: &Constraint, combinations: &[Vec<T>]) -> bool {
+  let mut removed = false;
+
+  let positions = constraint.positions();
+  let allowed = line_options(grid, constraint, combinations);
+  for (position, &allowed_options) in positions.iter().zip(allowed.iter()) {
+    if allowed_options != T::ZERO {                                 // Left for the consistency check if nothing allowed
+      removed |= remove_options(grid, position, !allowed_options);
+    }
+  }
+
+  removed
+}
+
+fn line_violation<T: OptionBits>(grid: &GridOf<T>, constraint: &Constraint, combinations: &[Vec<T>]) -> Option<Vec<Position>> {
+  if line_options(grid, constraint, combinations).contains(&T::ZERO) {
+    return Some(constraint.positions());
+  }
+
+  None
+}
+
+// Options of each of the constraint's cells (in the order of Constraint::positions) that can still meet it
+fn line_options<T: OptionBits>(grid: &GridOf<T>, constraint: &Constraint, combinations: &[Vec<T>]) -> Vec<T> {
+  let options: Vec<T> = constraint.positions().iter().map(|x| grid.get_cell(x).options).collect();
+
+  match constraint {
+    Constraint::Cage(_) => options,
+    Constraint::Thermometer(_) => thermometer_options(&options),
+    Constraint::Arrow(_) => arrow_options(grid, &options),
+    Constraint::Palindrome(_) => palindrome_options(&options),
+    Constraint::Sandwich(sandwich) => sandwich_options(grid, sandwich, &options, combinations),
+    Constraint::LittleKiller(little_killer) => sum_options(grid, little_killer.sum, &options)
+  }
+}
+
+// Each position next to the one before, along a row or column or diagonally
+fn joined<T: OptionBits>(grid: &GridOf<T>, positions: &[Position]) -> bool {
+  positions.windows(2).all(|pair| {
+    let ((column, row), (next_column, next_row)) = (grid.coordinates(&pair[0]), grid.coordinates(&pair[1]));
+    column.abs_diff(next_column) <= 1 && row.abs_diff(next_row) <= 1
+  })
+}
+
+// Positions one step apart in the same direction, diagonally or along a row or column
+fn straight<T: OptionBits>(grid: &GridOf<T>, positions: &[Position], diagonal: bool) -> bool {
+  let steps: Vec<(isize, isize)> = positions.windows(2)
+    .map(|pair| {
+      let ((column, row), (next_column, next_row)) = (grid.coordinates(&pair[0]), grid.coordinates(&pair[1]));
+      (next_column as isize - column as isize, next_row as isize - row as isize)
+    })
+    .collect();
+
+  steps.iter().all(|&(column_step, row_step)| match diagonal {
+    true => column_step.abs() == 1 && row_step.abs() == 1,
+    false => column_step.abs() + row_step.abs() == 1
+  }) && steps.windows(2).all(|pair| pair[0] == pair[1])
+}
+
+// Line from a cell on the edge of the grid across to the opposite edge i.e. with no cell before the first or after the
+//   last - any diagonal for a single cell, which must be in a corner
+fn edge_to_edge<T: OptionBits>(grid: &GridOf<T>, positions: &[Position]) -> bool {
+  let (columns, rows) = grid.dimensions();
+  let max_options = (columns * rows) as isize;
+  let outside = |column: isize, row: isize| column < 0 || row < 0 || column >= max_options || row >= max_options;
+
+  let coordinates = |position: &Position| {
+    let (column, row) = grid.coordinates(position);
+    (column as isize, row as isize)
+  };
+  let (first_column, first_row) = coordinates(&positions[0]);
+  let (last_column, last_row) = coordinates(&positions[positions.len() - 1]);
+
+  let steps = match positions.get(1).map(coordinates) {
+    Some((column, row)) => vec![(column - first_column, row - first_row)],
+    None => vec![(1, 1), (1, -1), (-1, 1), (-1, -1)]
+  };
+
+  steps.iter().any(|&(column_step, row_step)| {
+    outside(first_column - column_step, first_row - row_step) && outside(last_column + column_step, last_row + row_step)
+  })
+}
+
+// Options above the lowest option of the previous cell and below the highest option of the next cell
+fn thermometer_options<T: OptionBits>(options: &[T]) -> Vec<T> {
+  let mut allowed = options.to_vec();
+
+  for index in 1..allowed.len() {
+    let previous = allowed[index - 1];
+    allowed[index] &= match previous {
+      _ if previous == T::ZERO => T::ZERO,
+      _ => !T::mask(previous.trailing_zeros() + 1)
+    };
+  }
+
+  let mut index = allowed.len() - 1;
+  while index > 0 {
+    index -= 1;
+    let next = allowed[index + 1];
+    allowed[index] &= match next {
+      _ if next == T::ZERO => T::ZERO,
+      _ => T::mask(highest_bit_position(next))
+    };
+  }
+
+  allowed
+}
+
+// Circle's options (first) between the lowest and highest sums of the arrow, and each arrow cell's options between the
+//   lowest and highest circle values less the highest and lowest sums of the rest of the arrow
+fn arrow_options<T: OptionBits>(grid: &GridOf<T>, options: &[T]) -> Vec<T> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let arrow = &options[1..];
+  if arrow.contains(&T::ZERO) {
+    return vec![T::ZERO; options.len()];
+  }
+
+  let lowest_sum: usize = arrow.iter().map(|&x| lowest_value(x)).sum();
+  let highest_sum: usize = arrow.iter().map(|&x| highest_value(x)).sum();
+  let circle = options[0] & values(lowest_sum, highest_sum, max_options);
+  if circle == T::ZERO {
+    return vec![T::ZERO; options.len()];
+  }
+
+  let mut allowed = vec![circle];
+  for &cell_options in arrow.iter() {
+    let others_lowest = lowest_sum - lowest_value(cell_options);
+    let others_highest = highest_sum - highest_value(cell_options);
+    allowed.push(cell_options & values(
+      lowest_value(circle).saturating_sub(others_highest),
+      highest_value(circle) - others_lowest,
+      max_options
+    ));
+  }
+
+  allowed
+}
+
+// Options of each pair of cells that can take the lowest and highest values, with distinct values between them adding
+//   up to the sum that fit the cells between, and neither value nor the values between left for the other cells
+fn sandwich_options<T: OptionBits>(grid: &GridOf<T>, sandwich: &Sandwich, options: &[T], combinations: &[Vec<T>]) -> Vec<T> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+  let ends = [T::ONE, T::bit(max_options - 1)];
+
+  if combinations.is_empty() {                                      // Too many to work out, only checked once solved
+    return solved_sandwich_options(sandwich, options, ends);
+  }
+
+  let mut allowed = vec![T::ZERO; options.len()];
+  for first in 0..options.len() {
+    for second in first + 1..options.len() {
+      for &(first_end, second_end) in [(ends[0], ends[1]), (ends[1], ends[0])].iter() {
+        if options[first] & first_end == T::ZERO || options[second] & second_end == T::ZERO {
+          continue;
+        }
+
+        let between = &options[first + 1..second];
+        for &combination in combinations[between.len()].iter().filter(|&&x| fits(between, x)) {
+          let outside = !(combination | first_end | second_end);
+          let others = (0..options.len()).filter(|&x| x < first || x > second);
+          if others.clone().any(|x| options[x] & outside == T::ZERO) {
+            continue;
+          }
+
+          allowed[first] |= first_end;
+          allowed[second] |= second_end;
+          for index in first + 1..second {
+            allowed[index] |= options[index] & combination;
+          }
+          for index in others {
+            allowed[index] |= options[index] & outside;
+          }
+        }
+      }
+    }
+  }
+
+  allowed
+}
+
+// Options of a sandwich with every cell set, none if the values between don't add up to the sum - otherwise unchanged
+fn solved_sandwich_options<T: OptionBits>(sandwich: &Sandwich, options: &[T], ends: [T; 2]) -> Vec<T> {
+  if options.iter().any(|&x| number_of_bits_set(x) != 1) {
+    return options.to_vec();
+  }
+
+  let first = options.iter().position(|x| ends.contains(x));
+  let second = options.iter().rposition(|x| ends.contains(x));
+  match (first, second) {
+    (Some(first), Some(second)) if first < second => {
+      let sum: usize = options[first + 1..second].iter().map(|&x| lowest_value(x)).sum();
+      match sum == sandwich.sum {
+        true => options.to_vec(),
+        false => vec![T::ZERO; options.len()]
+      }
+    },
+    _ => vec![T::ZERO; options.len()]
+  }
+}
+
+// Options of each cell between the sum less the highest and lowest sums of the rest of the cells, repeats allowed
+fn sum_options<T: OptionBits>(grid: &GridOf<T>, sum: usize, options: &[T]) -> Vec<T> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  if options.contains(&T::ZERO) {
+    return vec![T::ZERO; options.len()];
+  }
+
+  let lowest_sum: usize = options.iter().map(|&x| lowest_value(x)).sum();
+  let highest_sum: usize = options.iter().map(|&x| highest_value(x)).sum();
+  if sum < lowest_sum || sum > highest_sum {
+    return vec![T::ZERO; options.len()];
+  }
+
+  options.iter()
+    .map(|&cell_options| {
+      let others_lowest = lowest_sum - lowest_value(cell_options);
+      let others_highest = highest_sum - highest_value(cell_options);
+      cell_options & values(sum.saturating_sub(others_highest), sum - others_lowest, max_options)
+    })
+    .collect()
+}
+
+// Options shared with the mirrored cell
+fn palindrome_options<T: OptionBits>(options: &[T]) -> Vec<T> {
+  options.iter().zip(options.iter().rev()).map(|(&x, &mirrored)| x & mirrored).collect()
+}
+
+// Value (option index + 1) of the lowest option
+fn lowest_value<T: OptionBits>(options: T) -> usize {
+  options.trailing_zeros() + 1
+}
+
+fn highest_value<T: OptionBits>(options: T) -> usize {
+  T::BITS - options.leading_zeros()
+}
+
+// Options of the values from lowest to highest, within the grid's options
+fn values<T: OptionBits>(lowest: usize, highest: usize, max_options: usize) -> T {
+  let highest = highest.min(max_options);
+  let lowest = lowest.max(1);
+  if lowest > highest {
+    return T::ZERO;
+  }
+
+  T::mask(highest) & !T::mask(lowest - 1)
+}
+
+// Options of each set of distinct values adding up to the cage's sum
+fn cage_combinations<T: OptionBits>(grid: &GridOf<T>, cage: &Cage) -> Vec<T> {
+  let (columns, rows) = grid.dimensions();
+  let bits: Vec<usize> = (0..columns * rows).collect();
+
+  sum_combinations(&bits, cage.positions.len(), cage.sum, usize::MAX).unwrap_or_default()
+}
+
+// Options of each set of distinct values between the lowest and highest adding up to the sandwich's sum, by the number
+//   of values - none if more than MAX_SANDWICH_COMBINATIONS in all
+fn sandwich_combinations<T: OptionBits>(grid: &GridOf<T>, sandwich: &Sandwich) -> Vec<Vec<T>> {
+  let (columns, rows) = grid.dimensions();
+  let bits: Vec<usize> = (1..columns * rows - 1).collect();
+
+  let mut combinations = Vec::new();
+  let mut limit = MAX_SANDWICH_COMBINATIONS;
+  for size in 0..=bits.len() {
+    match sum_combinations(&bits, size, sandwich.sum, limit) {
+      Some(sized) => {
+        limit -= sized.len();
+        combinations.push(sized);
+      },
+      None => return Vec::new()
+    }
+  }
+
+  combinations
+}
+
+// Options of each set of size distinct values from the bits (option indexes, in order) adding up to the sum - None if
+//   more than the limit
+fn sum_combinations<T: OptionBits>(bits: &[usize], size: usize, sum: usize, limit: usize) -> Option<Vec<T>> {
+  let mut combinations = Vec::new();
+  match add_sum_combinations(bits, size, sum, T::ZERO, limit, &mut combinations) {
+    true => Some(combinations),
+    false => None
+  }
+}
+
+// Combinations of the options so far with size more of the bits adding up to the rest of the sum, skipping the bits
+//   too low or high to reach it - false if over the limit
+fn add_sum_combinations<T: OptionBits>(bits: &[usize], size: usize, sum: usize, options: T, limit: usize, combinations: &mut Vec<T>) -> bool {
+  if size == 0 {
+    if sum == 0 {
+      if combinations.len() == limit {
+        return false;
+      }
+      combinations.push(options);
+    }
+    return true;
+  }
+
+  for index in 0..bits.len() {
+    let rest = &bits[index..];
+    if rest.len() < size {
+      break;
+    }
+
+    let lowest: usize = rest[..size].iter().map(|&x| x + 1).sum();
+    let highest: usize = rest[rest.len() - size..].iter().map(|&x| x + 1).sum();
+    if sum < lowest || sum > highest {
+      break;                                                        // Out of reach of any later first bit
+    }
+
+    if !add_sum_combinations(&bits[index + 1..], size - 1, sum - (bits[index] + 1), options | T::bit(bits[index]), limit, combinations) {
+      return false;
+    }
+  }
+
+  true
+}
+
+// Each cell can be given a different option of the combination
+fn fits<T: OptionBits>(options: &[T], combination: T) -> bool {
+  if number_of_bits_set(combination) != options.len() {
+    return false;
+  }
+
+  let mut matched: Vec<Option<usize>> = vec![None; T::BITS];        // Cell given each option
+  (0..options.len()).all(|cell| {
+    let mut visited = T::ZERO;
+    match_cell(options, combination, cell, &mut matched, &mut visited)
+  })
+}
+
+// Augmenting path from the cell to an option of the combination
+fn match_cell<T: OptionBits>(options: &[T], combination: T, cell: usize, matched: &mut [Option<usize>], visited: &mut T) -> bool {
+  let mut remaining = options[cell] & combination & !*visited;
+  while remaining != T::ZERO {
+    let option = remaining & !(remaining - T::ONE);
+    remaining -= option;
+    *visited |= option;
+
+    let bit = option.trailing_zeros();
+    let free = match matched[bit] {
+      None => true,
+      Some(other) => match_cell(options, combination, other, matched, visited)
+    };
+    if free {
+      matched[bit] = Some(cell);
+      return true;
+    }
+  }
+
+  false
+}
+
+fn remove_options<T: OptionBits>(grid: &mut GridOf<T>, position: &Position, options: T) -> bool {
+  let mut removed = false;
+  let mut remaining = options & grid.get_cell(position).options;
+  while remaining != T::ZERO {
+    let option = remaining & !(remaining - T::ONE);                 // lowest set bit value
+    remaining -= option;
+    let before = grid.get_cell(position).options;
+    grid.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+    removed |= grid.get_cell(position).options != before;
+  }
+
+  removed
+}
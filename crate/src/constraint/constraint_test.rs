@@ -0,0 +1,370 @@
+#[cfg(test)]
+fn killer() -> crate::grid::grid::Grid {
+  use crate::constraint::Constraint;
+  use crate::format::format;
+  use crate::grid::grid::Grid;
+
+  let cages = format::parse_cages(2, 2, concat!(
+    "a a | b b\n",
+    "c d | e b\n",
+    "----+----\n",
+    "f d | g i\n",
+    "f h | h i\n",
+    "a=3 b=9 c=3 d=5 e=1 f=6 g=4 h=5 i=4"
+  )).unwrap();
+
+  let mut grid = Grid::new(2, 2);
+  for cage in cages.into_iter() {
+    grid.add_constraint(Constraint::Cage(cage)).unwrap();
+  }
+
+  grid
+}
+
+#[cfg(test)]
+mod cage {
+  use crate::cell::SetMethod;
+  use crate::constraint::{constraint, Constraint, Cage};
+  use crate::error::SolverError;
+  use crate::grid::{grid::Grid, Contradiction, Position};
+
+  fn cage(grid: &Grid, sum: usize, coordinates: &[(usize, usize)]) -> Constraint {
+    Constraint::Cage(Cage { sum, positions: coordinates.iter().map(|&(x, y)| grid.position(x, y)).collect() })
+  }
+
+  #[test]
+  fn it_keeps_the_options_adding_up_to_the_sum() {
+    let mut grid = Grid::new(2, 2);
+    grid.add_constraint(cage(&grid, 3, &[(0, 0), (1, 0)])).unwrap();
+    grid.add_constraint(cage(&grid, 7, &[(0, 3), (1, 3)])).unwrap();
+    grid.simplify();
+
+    assert_eq!(grid.get_cell(&grid.position(0, 0)).options, 1 + 2);
+    assert_eq!(grid.get_cell(&grid.position(1, 0)).options, 1 + 2);
+    assert_eq!(grid.get_cell(&grid.position(1, 3)).options, 4 + 8);
+  }
+
+  #[test]
+  fn it_works_out_the_sums_once_added() {
+    let grid = Grid::new(2, 2);
+    let combinations = constraint::combinations(&grid, &cage(&grid, 5, &[(0, 0), (1, 0)]));
+
+    assert_eq!(combinations.len(), 3);                              // By number of cells, only 2 for the cage
+    assert!(combinations[0].is_empty() && combinations[1].is_empty());
+    assert_eq!(combinations[2], vec![1 + 8, 2 + 4]);                // 1 + 4 and 2 + 3
+  }
+
+  #[test]
+  fn it_removes_repeats() {
+    let mut grid = Grid::new(2, 2);
+    grid.add_constraint(cage(&grid, 5, &[(0, 0), (1, 1)])).unwrap();   // Diagonal within a sub-grid: 1 + 4 or 2 + 3
+    grid.set_by_option(0, 0, 0, 0, 2, SetMethod::Loaded);
+    grid.simplify();
+
+    assert_eq!(grid.get_cell(&grid.position(1, 1)).options, 4);
+  }
+
+  #[test]
+  fn it_finds_a_broken_cage() {
+    let mut grid = Grid::new(2, 2);
+    grid.add_constraint(cage(&grid, 4, &[(0, 0), (0, 2)])).unwrap();
+    grid.set_by_option(0, 0, 0, 0, 2, SetMethod::Loaded);
+    grid.set_by_option(0, 1, 0, 0, 4, SetMethod::Loaded);           // 2 + 3
+
+    let positions = vec![grid.position(0, 0), grid.position(0, 2)];
+    assert_eq!(grid.check_consistency(), Some(Contradiction::BrokenConstraint { constraint: 0, positions }));
+  }
+
+  #[test]
+  fn it_rejects_invalid_cages() {
+    let mut grid = Grid::new(2, 2);
+
+    let position = Position { sub_grid_column: 2, sub_grid_row: 0, cell_column: 0, cell_row: 0 };
+    let invalid = Constraint::Cage(Cage { sum: 1, positions: vec![position] });
+    assert_eq!(grid.add_constraint(invalid), Err(SolverError::InvalidPosition(position)));
+    assert!(matches!(grid.add_constraint(cage(&grid, 2, &[(0, 0), (0, 0)])), Err(SolverError::InvalidConstraint(_))));
+    assert!(matches!(grid.add_constraint(cage(&grid, 2, &[])), Err(SolverError::InvalidConstraint(_))));
+    assert!(grid.constraints().is_empty());
+  }
+
+  #[test]
+  fn it_solves_a_killer_sudoku() {
+    let mut grid = super::killer();
+
+    assert!(grid.count_solutions(2).unique());
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), "1234341221434321");
+
+    grid.reset();                                                   // Cages kept
+    assert_eq!(grid.constraints().len(), 9);
+  }
+
+  #[test]
+  fn it_round_trips_through_json() {
+    let grid = super::killer();
+    let json = constraint::to_json(grid.constraints());
+
+    assert!(json.starts_with(r#"[{"cage":{"sum":3,"positions":[{"subGridColumn":0,"subGridRow":0,"cellColumn":0,"#));
+    assert_eq!(constraint::from_json(&json).unwrap(), grid.constraints());
+    assert!(matches!(constraint::from_json("[{\"cage\":1}]"), Err(SolverError::InvalidJson(_))));
+  }
+}
+
+#[cfg(test)]
+mod lines {
+  use crate::cell::SetMethod;
+  use crate::constraint::{constraint, Constraint, Thermometer, Arrow, Palindrome};
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, Contradiction};
+
+  fn positions(grid: &Grid, coordinates: &[(usize, usize)]) -> Vec<crate::grid::Position> {
+    coordinates.iter().map(|&(x, y)| grid.position(x, y)).collect()
+  }
+
+  fn lines() -> Grid {
+    let mut grid = Grid::new(2, 2);
+    let constraints = vec![
+      Constraint::Thermometer(Thermometer { positions: positions(&grid, &[(0, 0), (1, 0), (2, 0), (3, 0)]) }),
+      Constraint::Arrow(Arrow { circle: grid.position(0, 1), positions: positions(&grid, &[(1, 2), (0, 2)]) }),
+      Constraint::Palindrome(Palindrome { positions: positions(&grid, &[(0, 3), (1, 3), (2, 2)]) })
+    ];
+    for item in constraints.into_iter() {
+      grid.add_constraint(item).unwrap();
+    }
+
+    grid
+  }
+
+  #[test]
+  fn it_keeps_a_thermometer_increasing() {
+    let mut grid = Grid::new(2, 2);
+    let thermometer = Thermometer { positions: positions(&grid, &[(0, 1), (1, 2), (2, 1)]) };
+    grid.add_constraint(Constraint::Thermometer(thermometer)).unwrap();
+    grid.simplify();
+
+    assert_eq!(grid.get_cell(&grid.position(0, 1)).options, 1 + 2);
+    assert_eq!(grid.get_cell(&grid.position(1, 2)).options, 2 + 4);
+    assert_eq!(grid.get_cell(&grid.position(2, 1)).options, 4 + 8);
+  }
+
+  #[test]
+  fn it_keeps_an_arrow_summing_to_its_circle() {
+    let mut grid = Grid::new(2, 2);
+    let arrow = Arrow { circle: grid.position(0, 0), positions: positions(&grid, &[(1, 0), (2, 0)]) };
+    grid.add_constraint(Constraint::Arrow(arrow)).unwrap();
+    grid.simplify();
+
+    assert_eq!(grid.get_cell(&grid.position(0, 0)).options, 2 + 4 + 8);
+    assert_eq!(grid.get_cell(&grid.position(1, 0)).options, 1 + 2 + 4);
+    assert_eq!(grid.get_cell(&grid.position(2, 0)).options, 1 + 2 + 4);
+  }
+
+  #[test]
+  fn it_mirrors_a_palindrome() {
+    let mut grid = Grid::new(2, 2);
+    let palindrome = Palindrome { positions: positions(&grid, &[(0, 0), (1, 1), (2, 2)]) };
+    grid.add_constraint(Constraint::Palindrome(palindrome)).unwrap();
+    grid.set_by_option(0, 0, 0, 0, 2, SetMethod::Loaded);
+    grid.simplify();
+
+    assert_eq!(grid.get_cell(&grid.position(2, 2)).options, 2);
+  }
+
+  #[test]
+  fn it_finds_a_broken_thermometer() {
+    let mut grid = Grid::new(2, 2);
+    grid.add_constraint(Constraint::Thermometer(Thermometer { positions: positions(&grid, &[(0, 0), (1, 0)]) })).unwrap();
+    grid.set_by_option(0, 0, 0, 0, 4, SetMethod::Loaded);
+    grid.set_by_option(0, 0, 1, 0, 2, SetMethod::Loaded);
+
+    let positions = positions(&grid, &[(0, 0), (1, 0)]);
+    assert_eq!(grid.check_consistency(), Some(Contradiction::BrokenConstraint { constraint: 0, positions }));
+  }
+
+  #[test]
+  fn it_rejects_invalid_lines() {
+    let mut grid = Grid::new(2, 2);
+    let circle = grid.position(0, 0);
+
+    let thermometer = Thermometer { positions: positions(&grid, &[(0, 0)]) };
+    assert!(matches!(grid.add_constraint(Constraint::Thermometer(thermometer)), Err(SolverError::InvalidConstraint(_))));
+    let arrow = Arrow { circle, positions: vec![circle] };
+    assert!(matches!(grid.add_constraint(Constraint::Arrow(arrow)), Err(SolverError::InvalidConstraint(_))));
+    assert!(grid.constraints().is_empty());
+  }
+
+  #[test]
+  fn it_rejects_lines_not_joined_up() {
+    let mut grid = Grid::new(2, 2);
+    let circle = grid.position(0, 0);
+
+    let thermometer = Thermometer { positions: positions(&grid, &[(0, 0), (1, 1), (3, 1)]) };
+    assert!(matches!(grid.add_constraint(Constraint::Thermometer(thermometer)), Err(SolverError::InvalidConstraint(_))));
+    let arrow = Arrow { circle, positions: positions(&grid, &[(0, 2), (1, 2)]) };   // Not next to the circle
+    assert!(matches!(grid.add_constraint(Constraint::Arrow(arrow)), Err(SolverError::InvalidConstraint(_))));
+    let palindrome = Palindrome { positions: positions(&grid, &[(3, 3), (2, 2), (0, 2)]) };
+    assert!(matches!(grid.add_constraint(Constraint::Palindrome(palindrome)), Err(SolverError::InvalidConstraint(_))));
+    assert!(grid.constraints().is_empty());
+
+    let arrow = Arrow { circle, positions: positions(&grid, &[(1, 1), (2, 0)]) };   // Diagonal steps allowed
+    assert!(grid.add_constraint(Constraint::Arrow(arrow)).is_ok());
+  }
+
+  #[test]
+  fn it_solves_with_lines_read_from_json() {
+    let json = constraint::to_json(lines().constraints());
+    assert!(json.contains(r#"{"arrow":{"circle":{"subGridColumn":0,"subGridRow":0,"cellColumn":0,"cellRow":1},"#));
+
+    let mut grid = Grid::new(2, 2);
+    for item in constraint::from_json(&json).unwrap().into_iter() {
+      grid.add_constraint(item).unwrap();
+    }
+    grid.load_set_options(&format::parse(2, 2, "...............1").unwrap());
+
+    assert!(grid.count_solutions(2).unique());
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), "1234341221434321");
+  }
+}
+
+#[cfg(test)]
+mod outside {
+  use crate::constraint::{constraint, Constraint, Sandwich, LittleKiller};
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::cell::SetMethod;
+  use crate::grid::{grid::Grid, House, Position};
+
+  fn with_clues(columns: usize, rows: usize, text: &str) -> Grid {
+    let mut grid = Grid::new(columns, rows);
+    for item in format::parse_outside_clues(columns, rows, text).unwrap().into_iter() {
+      grid.add_constraint(item).unwrap();
+    }
+
+    grid
+  }
+
+  #[test]
+  fn it_keeps_the_lowest_and_highest_around_a_sandwich() {
+    let mut grid = with_clues(3, 2, "c1=14");
+    grid.simplify();
+
+    let ends = 1 + 32;
+    let options: Vec<u64> = (0..6).map(|row| grid.get_cell(&grid.position(0, row)).options).collect();
+    assert_eq!(options, vec![ends, 0b011110, 0b011110, 0b011110, 0b011110, ends]);  // 2 + 3 + 4 + 5 between
+  }
+
+  #[test]
+  fn it_keeps_the_options_of_a_little_killer_sum() {
+    let mut grid = with_clues(2, 2, "r1c3se=7");
+    grid.simplify();
+
+    assert_eq!(grid.get_cell(&grid.position(2, 0)).options, 4 + 8);
+    assert_eq!(grid.get_cell(&grid.position(3, 1)).options, 4 + 8);
+
+    let mut grid = with_clues(2, 2, "r1c2se=3");
+    grid.simplify();
+    for index in 0..3 {
+      assert_eq!(grid.get_cell(&grid.position(index + 1, index)).options, 1);  // Only 1s, repeated in other sub-grids
+    }
+  }
+
+  #[test]
+  fn it_finds_a_broken_sandwich() {
+    let mut grid = with_clues(2, 2, "r1=5");
+    grid.load_set_options(&format::parse(2, 2, "1423............").unwrap());
+
+    assert!(grid.check_consistency().is_some());
+  }
+
+  #[test]
+  fn it_works_out_the_sandwich_sums_once_added() {
+    let grid = Grid::new(6, 6);
+    let positions = grid.get_house_positions(&House::Row(0));
+
+    let combinations = constraint::combinations(&grid, &Constraint::Sandwich(Sandwich { sum: 5, positions: positions.clone() }));
+    assert_eq!(combinations.len(), 35);                             // By number of values between, up to 34
+    assert_eq!(combinations[1], vec![16]);
+    assert_eq!(combinations[2], vec![2 + 4]);                       // 2 + 3
+    assert!(combinations[3].is_empty());
+
+    let combinations = constraint::combinations(&grid, &Constraint::Sandwich(Sandwich { sum: 300, positions }));
+    assert!(combinations.is_empty());                               // Too many to keep
+  }
+
+  #[test]
+  fn it_checks_a_sandwich_with_too_many_sums_once_solved() {
+    let mut grid = Grid::new(6, 6);
+    let positions = grid.get_house_positions(&House::Row(0));
+    grid.add_constraint(Constraint::Sandwich(Sandwich { sum: 300, positions: positions.clone() })).unwrap();
+
+    for (index, position) in positions.iter().enumerate() {
+      let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = *position;
+      grid.set_by_option(sub_grid_column, sub_grid_row, cell_column, cell_row, 1 << index, SetMethod::Loaded);
+    }
+    assert!(grid.check_consistency().is_some());                    // 2 + 3 + ... + 35 between
+  }
+
+  #[test]
+  fn it_rejects_clues_not_in_a_line() {
+    let mut grid = Grid::new(2, 2);
+
+    let mut positions = grid.get_house_positions(&House::Row(0));
+    positions.swap(0, 1);
+    assert!(matches!(grid.add_constraint(Constraint::Sandwich(Sandwich { sum: 5, positions })), Err(SolverError::InvalidConstraint(_))));
+    let positions = vec![grid.position(0, 0), grid.position(1, 1), grid.position(2, 1)];
+    let little_killer = LittleKiller { sum: 6, positions };
+    assert!(matches!(grid.add_constraint(Constraint::LittleKiller(little_killer)), Err(SolverError::InvalidConstraint(_))));
+    assert!(grid.constraints().is_empty());
+  }
+
+  #[test]
+  fn it_rejects_little_killers_not_from_edge_to_edge() {
+    let mut grid = Grid::new(2, 2);
+
+    let invalid = [
+      vec![grid.position(1, 1), grid.position(2, 2), grid.position(3, 3)],  // Not from the edge
+      vec![grid.position(0, 0), grid.position(1, 1), grid.position(2, 2)],  // Not to the opposite edge
+      vec![grid.position(1, 0)]
+    ];
+    for positions in invalid.iter() {
+      let little_killer = LittleKiller { sum: 6, positions: positions.clone() };
+      assert!(matches!(grid.add_constraint(Constraint::LittleKiller(little_killer)), Err(SolverError::InvalidConstraint(_))));
+    }
+    assert!(grid.constraints().is_empty());
+
+    let positions = vec![grid.position(3, 0)];                      // Corner
+    assert!(grid.add_constraint(Constraint::LittleKiller(LittleKiller { sum: 4, positions })).is_ok());
+  }
+
+  #[test]
+  fn it_solves_with_sandwiches() {
+    let givens = format::parse(3, 2, "...........................26....1..").unwrap();
+    let mut standard = Grid::new(3, 2);
+    standard.load_set_options(&givens);
+    assert_eq!(standard.count_solutions(2).total, 2);
+
+    let mut grid = with_clues(3, 2, "c3=11 c4=10 r5=7 c5=2 c6=6");
+    grid.load_set_options(&givens);
+    assert!(grid.count_solutions(2).unique());
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), "123456541632362514654321415263236145");
+  }
+
+  #[test]
+  fn it_solves_with_little_killers_read_from_json() {
+    let clues = with_clues(3, 2, "r1c3sw=10 r3c6sw=14 r1c4sw=17 r4c6sw=8 r1c5se=7 r5c1se=7 r1c5sw=22 r5c6sw=7");
+    let json = constraint::to_json(clues.constraints());
+    assert!(json.starts_with(r#"[{"littleKiller":{"sum":10,"positions":[{"subGridColumn":1,"subGridRow":0,"cellColumn":0,"#));
+
+    let mut grid = Grid::new(3, 2);
+    for item in constraint::from_json(&json).unwrap().into_iter() {
+      grid.add_constraint(item).unwrap();
+    }
+
+    assert!(grid.count_solutions(2).unique());                      // No givens at all
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), "123456541632362514654321415263236145");
+  }
+}
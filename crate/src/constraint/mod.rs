@@ -0,0 +1,89 @@
+use std::fmt::{self, Display};
+use serde::{Serialize, Deserialize};
+
+use crate::grid::Position;
+
+pub mod constraint;
+
+mod constraint_test;
+
+// Rule on top of the columns, rows and sub-grids, applied while simplifying and checked for consistency
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Constraint {
+  Cage(Cage),
+  Thermometer(Thermometer),
+  Arrow(Arrow),
+  Palindrome(Palindrome),
+  Sandwich(Sandwich),
+  LittleKiller(LittleKiller)
+}
+
+impl Constraint {
+  pub fn positions(&self) -> Vec<Position> {
+    match self {
+      Constraint::Cage(cage) => cage.positions.clone(),
+      Constraint::Thermometer(thermometer) => thermometer.positions.clone(),
+      Constraint::Arrow(arrow) => std::iter::once(arrow.circle).chain(arrow.positions.iter().copied()).collect(),
+      Constraint::Palindrome(palindrome) => palindrome.positions.clone(),
+      Constraint::Sandwich(sandwich) => sandwich.positions.clone(),
+      Constraint::LittleKiller(little_killer) => little_killer.positions.clone()
+    }
+  }
+}
+
+impl Display for Constraint {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Constraint::Cage(cage) => write!(formatter, "cage of {} cells summing to {}", cage.positions.len(), cage.sum),
+      Constraint::Thermometer(thermometer) => write!(formatter, "thermometer of {} cells", thermometer.positions.len()),
+      Constraint::Arrow(arrow) => write!(formatter, "arrow of {} cells from {}", arrow.positions.len(), arrow.circle),
+      Constraint::Palindrome(palindrome) => write!(formatter, "palindrome of {} cells", palindrome.positions.len()),
+      Constraint::Sandwich(sandwich) => write!(formatter, "sandwich of {} along {} cells", sandwich.sum, sandwich.positions.len()),
+      Constraint::LittleKiller(little_killer) => write!(
+        formatter, "little killer of {} cells summing to {}", little_killer.positions.len(), little_killer.sum
+      )
+    }
+  }
+}
+
+// Killer sudoku cage: cells with no repeated options, whose values (option index + 1) add up to the sum
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cage {
+  pub sum: usize,
+  pub positions: Vec<Position>
+}
+
+// Values strictly increasing from the bulb i.e. the first cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Thermometer {
+  pub positions: Vec<Position>
+}
+
+// Value of the circle equal to the sum of the values along the arrow, which may repeat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Arrow {
+  pub circle: Position,
+  pub positions: Vec<Position>
+}
+
+// Same values read from either end i.e. each cell equal to its mirrored cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palindrome {
+  pub positions: Vec<Position>
+}
+
+// Outside clue of a whole row or column: values between its lowest and highest symbols (in either order) add up to the
+//   sum, 0 if they're next to each other
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sandwich {
+  pub sum: usize,
+  pub positions: Vec<Position>
+}
+
+// Outside clue of a diagonal from the edge of the grid: values add up to the sum, and may repeat across sub-grids
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LittleKiller {
+  pub sum: usize,
+  pub positions: Vec<Position>
+}
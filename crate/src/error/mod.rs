@@ -0,0 +1,68 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::cell::SYMBOLS;
+use crate::grid::{Position, Contradiction, Technique};
+use crate::utils::bit_utils::OptionBits;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SolverError<T: OptionBits = u64> {
+  InvalidDimensions { columns: usize, rows: usize },
+  InvalidInputLength { expected: usize, actual: usize },
+  InvalidOption { index: usize, option: T },                        // Not 0 or a single bit within the grid's options
+  InvalidSymbol(char),
+  InvalidOptionPosition { column: usize, row: usize },              // Outside the columns and rows of a cell's options
+  InvalidPosition(Position),
+  DuplicateGiven { position: Position, symbol: char },              // Symbol already set in the same row, column or sub-grid
+  Contradiction(Contradiction<T>),
+  InvalidJson(String),
+  UnknownLayout(usize),                                             // Number of symbols not a supported grid size
+  NotUnique(usize),                                                 // Number of solutions found, up to 2
+  InvalidConstraint(String),
+  InvalidHouse(String),
+  InvalidSharedSubGrid(String),
+  InvalidGrid { index: usize, total: usize },                       // Not one of a multi-grid's grids
+  InvalidHistory(String),                                           // Moves that don't replay to the grid's state
+  DifficultyNotReached(Technique)                                   // No generated puzzle rated with it as the hardest
+}
+
+impl<T: OptionBits> Display for SolverError<T> {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      SolverError::InvalidDimensions { columns, rows } => write!(
+        formatter,
+        "Invalid dimensions {} x {}: columns and rows must be at least 1, with at most {} symbols",
+        columns,
+        rows,
+        SYMBOLS.len().min(T::BITS)
+      ),
+      SolverError::InvalidInputLength { expected, actual } =>
+        write!(formatter, "Invalid input length {}: expected {} options", actual, expected),
+      SolverError::InvalidOption { index, option } =>
+        write!(formatter, "Invalid option {} at index {}: expected 0 or a single option bit", option, index),
+      SolverError::InvalidSymbol(symbol) => write!(formatter, "Invalid symbol '{}'", symbol),
+      SolverError::InvalidOptionPosition { column, row } =>
+        write!(formatter, "Invalid option position: column {}, row {} outside the cell's options", column, row),
+      SolverError::InvalidPosition(position) => write!(formatter, "Invalid position: {}", position),
+      SolverError::DuplicateGiven { position, symbol } =>
+        write!(formatter, "Duplicate given '{}' at {}", symbol, position),
+      SolverError::Contradiction(contradiction) =>
+        write!(formatter, "The puzzle contains a contradiction and cannot be solved: {}", contradiction),
+      SolverError::InvalidJson(error) => write!(formatter, "Invalid JSON: {}", error),
+      SolverError::UnknownLayout(total_symbols) =>
+        write!(formatter, "Unknown layout: {} symbols is not the size of a supported grid", total_symbols),
+      SolverError::NotUnique(0) => write!(formatter, "The givens have no solution"),
+      SolverError::NotUnique(_) => write!(formatter, "The givens have more than one solution"),
+      SolverError::InvalidConstraint(error) => write!(formatter, "Invalid constraint: {}", error),
+      SolverError::InvalidHouse(error) => write!(formatter, "Invalid house: {}", error),
+      SolverError::InvalidSharedSubGrid(error) => write!(formatter, "Invalid shared sub-grid: {}", error),
+      SolverError::InvalidGrid { index, total } =>
+        write!(formatter, "Invalid grid {}: expected one of {} grids", index, total),
+      SolverError::InvalidHistory(error) => write!(formatter, "Invalid history: {}", error),
+      SolverError::DifficultyNotReached(technique) =>
+        write!(formatter, "No puzzle generated with {} as the hardest technique", technique)
+    }
+  }
+}
+
+impl<T: OptionBits> Error for SolverError<T> {}
@@ -0,0 +1,446 @@
+use std::str::FromStr;
+
+use crate::cell::SYMBOLS;
+use crate::error::SolverError;
+use crate::format::{BLANKS, SEPARATORS, NO_CAGE, CAGE_IDS, NO_HOUSE};
+use crate::constraint::{Constraint, Cage, Sandwich, LittleKiller};
+use crate::grid::{grid::{Grid, GridOf, LargeGrid}, House, Position};
+use crate::utils::bit_utils::OptionBits;
+
+// Set options, as loaded by Grid::load_set_options, of a puzzle written row by row e.g. "4.....8.5.3...." or as an
+//   ASCII grid with box separators
+pub fn parse(columns: usize, rows: usize, text: &str) -> Result<Vec<u64>, SolverError> {
+  parse_options(columns, rows, text)
+}
+
+// Set options as parsed by parse, for grids with other option bits e.g. a LargeGrid
+pub fn parse_options<T: OptionBits>(columns: usize, rows: usize, text: &str) -> Result<Vec<T>, SolverError<T>> {
+  let grid = GridOf::<T>::try_new(columns, rows)?;
+  let max_options = columns * rows;
+  let symbols = symbols(text);
+
+  let expected = max_options * max_options;
+  if symbols.len() != expected {
+    return Err(SolverError::InvalidInputLength { expected, actual: symbols.len() });
+  }
+
+  let mut options = vec![T::ZERO; expected];
+  for (index, &symbol) in symbols.iter().enumerate() {
+    let position = grid.position(index % max_options, index / max_options);
+    options[grid.options_index(&position)] = option(symbol, max_options)?;
+  }
+
+  Ok(options)
+}
+
+// Grid's columns and rows for a puzzle of total_symbols symbols, with sub-grids at least as wide as they are high
+pub fn dimensions(total_symbols: usize) -> Result<(usize, usize), SolverError> {
+  let mut max_options = 1;
+  while max_options * max_options < total_symbols {
+    max_options += 1;
+  }
+
+  if total_symbols == 0 || max_options * max_options != total_symbols || max_options > SYMBOLS.len() {
+    return Err(SolverError::UnknownLayout(total_symbols));
+  }
+
+  let mut sub_grid_rows = 1;                                        // Grid's columns i.e. swopped
+  for rows in 1..=max_options {
+    if max_options % rows == 0 && rows * rows <= max_options {
+      sub_grid_rows = rows;
+    }
+  }
+
+  Ok((sub_grid_rows, max_options / sub_grid_rows))
+}
+
+// Row by row with blank used for unsolved cells
+pub fn to_line<T: OptionBits>(grid: &GridOf<T>, blank: char) -> String {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let mut line = String::with_capacity(max_options * max_options);
+  for row in 0..max_options {
+    for column in 0..max_options {
+      line.push(symbol(grid, column, row, blank));
+    }
+  }
+
+  line
+}
+
+// Row by row with the sub-grids separated e.g.
+//   1 . | . 4
+//   . 4 | 1 .
+//   ----+----
+pub fn to_ascii<T: OptionBits>(grid: &GridOf<T>) -> String {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let separator_line = vec!["-".repeat(2 * rows - 1); columns].join("-+-");
+
+  let mut output = String::new();
+  for row in 0..max_options {
+    if row > 0 && row % columns == 0 {                              // Sub-grid's rows i.e. swopped columns
+      output.push_str(&separator_line);
+      output.push('\n');
+    }
+
+    for column in 0..max_options {
+      if column > 0 {
+        output.push_str(if column % rows == 0 { " | " } else { " " });
+      }
+      output.push(symbol(grid, column, row, '.'));
+    }
+    output.push('\n');
+  }
+
+  output
+}
+
+// Options of each cell, as loaded by Grid::load_options, of a candidate grid written row by row with each cell's symbols
+//   in braces e.g. "{4}{126}{3}..." or separated by whitespace e.g. "4 126 3 | ...", ignoring box separators
+pub fn parse_candidates(columns: usize, rows: usize, text: &str) -> Result<Vec<u64>, SolverError> {
+  parse_candidate_options(columns, rows, text)
+}
+
+// Options as parsed by parse_candidates, for grids with other option bits e.g. a LargeGrid
+pub fn parse_candidate_options<T: OptionBits>(columns: usize, rows: usize, text: &str) -> Result<Vec<T>, SolverError<T>> {
+  let grid = GridOf::<T>::try_new(columns, rows)?;
+  let max_options = columns * rows;
+  let cells = candidate_cells(text);
+
+  let expected = max_options * max_options;
+  if cells.len() != expected {
+    return Err(SolverError::InvalidInputLength { expected, actual: cells.len() });
+  }
+
+  let mut options = vec![T::ZERO; expected];
+  for (index, symbols) in cells.iter().enumerate() {
+    let position = grid.position(index % max_options, index / max_options);
+    let options_index = grid.options_index(&position);
+    for &symbol in symbols.iter() {
+      options[options_index] |= option(symbol, max_options)?;
+    }
+
+    if options[options_index] == T::ZERO {
+      return Err(SolverError::InvalidOption { index: options_index, option: T::ZERO });
+    }
+  }
+
+  Ok(options)
+}
+
+// Grid with the candidates, working out the layout from the number of cells
+pub fn load_candidates(text: &str) -> Result<Grid, SolverError> {
+  load_candidates_of(text)
+}
+
+// Grid as loaded by load_candidates, with other option bits e.g. a LargeGrid
+pub fn load_candidates_of<T: OptionBits>(text: &str) -> Result<GridOf<T>, SolverError<T>> {
+  let total_cells = candidate_cells(text).len();
+  let (columns, rows) = dimensions(total_cells).map_err(|_| SolverError::UnknownLayout(total_cells))?;
+
+  let mut grid = GridOf::try_new(columns, rows)?;
+  grid.try_load_options(&parse_candidate_options(columns, rows, text)?)?;
+
+  Ok(grid)
+}
+
+// Remaining options of each cell, padded to line up each column, with the sub-grids separated e.g.
+//   1   34 | 234 4
+//   34  2  | 1   34
+//   -------+-------
+pub fn to_candidates<T: OptionBits>(grid: &GridOf<T>) -> String {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let cells: Vec<Vec<String>> = (0..max_options).map(|row| (0..max_options).map(|column| {
+    let options = grid.get_cell(&grid.position(column, row)).options;
+    (0..max_options).filter(|&index| options & T::bit(index) != T::ZERO).map(|index| SYMBOLS[index]).collect()
+  }).collect()).collect();
+
+  let widths: Vec<usize> = (0..max_options)
+    .map(|column| cells.iter().map(|row_cells| row_cells[column].chars().count()).max().unwrap_or(1))
+    .collect();
+
+  let separator_line = widths.chunks(rows)
+    .map(|sub_grid_widths| "-".repeat(sub_grid_widths.iter().sum::<usize>() + sub_grid_widths.len() - 1))
+    .collect::<Vec<String>>()
+    .join("-+-");
+
+  let mut output = String::new();
+  for (row, row_cells) in cells.iter().enumerate() {
+    if row > 0 && row % columns == 0 {                              // Sub-grid's rows i.e. swopped columns
+      output.push_str(&separator_line);
+      output.push('\n');
+    }
+
+    let mut line = String::new();
+    for (column, cell) in row_cells.iter().enumerate() {
+      if column > 0 {
+        line.push_str(if column % rows == 0 { " | " } else { " " });
+      }
+      line.push_str(&format!("{:<1$}", cell, widths[column]));
+    }
+    output.push_str(line.trim_end());
+    output.push('\n');
+  }
+
+  output
+}
+
+impl<T: OptionBits> FromStr for GridOf<T> {
+  type Err = SolverError<T>;
+
+  // Load the givens of a puzzle in any of the parsed formats, working out the layout from the number of symbols
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let total_symbols = symbols(text).len();
+    let (columns, rows) = dimensions(total_symbols).map_err(|_| SolverError::UnknownLayout(total_symbols))?;
+
+    let mut grid = Self::try_new(columns, rows)?;
+    grid.try_load_set_options(&parse_options(columns, rows, text)?)?;
+
+    Ok(grid)
+  }
+}
+
+// Killer cages of a layout written row by row with the id of each cell's cage ('.' for none), followed by each cage's
+//   sum e.g. "a a b | ...  a=3 b=12 ..." - in the order the cages first appear
+pub fn parse_cages(columns: usize, rows: usize, text: &str) -> Result<Vec<Cage>, SolverError> {
+  let grid = layout(columns, rows)?;
+
+  let (sum_tokens, cell_tokens): (Vec<&str>, Vec<&str>) = text.split_whitespace().partition(|x| x.contains('='));
+  let (cage_ids, mut cages): (Vec<char>, Vec<Cage>) = group_ids(&grid, &cell_tokens.join(" "), NO_CAGE)?.into_iter()
+    .map(|(id, positions)| (id, Cage { sum: 0, positions }))
+    .unzip();
+
+  let mut summed = vec![false; cages.len()];
+  for token in sum_tokens.iter() {
+    let (id, sum) = token.split_once('=').expect("Sum tokens contain '='");
+    let cage = match id.chars().collect::<Vec<char>>()[..] {
+      [id] => cage_ids.iter().position(|&x| x == id),
+      _ => None
+    };
+
+    match (cage, sum.parse::<usize>()) {
+      (Some(cage), Ok(sum)) => {
+        cages[cage].sum = sum;
+        summed[cage] = true;
+      },
+      _ => return Err(SolverError::InvalidConstraint(format!("Unknown cage or sum '{}'", token)))
+    }
+  }
+
+  if let Some(cage) = summed.iter().position(|&x| !x) {
+    return Err(SolverError::InvalidConstraint(format!("No sum for cage '{}'", cage_ids[cage])));
+  }
+
+  Ok(cages)
+}
+
+// Houses of a layout written row by row with the id of each cell's house ('.' for none) e.g. jigsaw regions or Windoku
+//   windows "a a a b | ..." - in the order the houses first appear
+pub fn parse_houses(columns: usize, rows: usize, text: &str) -> Result<Vec<Vec<Position>>, SolverError> {
+  let grid = layout(columns, rows)?;
+
+  Ok(group_ids(&grid, text, NO_HOUSE)?.into_iter().map(|(_, positions)| positions).collect())
+}
+
+// Outside clues, a token each with rows and columns from 1: the sandwich sum of a row or column e.g. "r3=12 c1=0", or
+//   the little killer sum of the diagonal from a cell heading ne, nw, se or sw e.g. "r1c2se=15"
+pub fn parse_outside_clues(columns: usize, rows: usize, text: &str) -> Result<Vec<Constraint>, SolverError> {
+  let grid = layout(columns, rows)?;
+
+  text.split_whitespace()
+    .map(|token| {
+      let clue = match token.split_once('=') {
+        Some((key, sum)) => sum.parse::<usize>().ok().and_then(|sum| outside_clue(&grid, key, sum)),
+        None => None
+      };
+
+      clue.ok_or_else(|| SolverError::InvalidConstraint(format!("Unknown outside clue '{}'", token)))
+    })
+    .collect()
+}
+
+// Grid's killer cages as read by parse_cages, with the sub-grids separated
+pub fn to_cages<T: OptionBits>(grid: &GridOf<T>) -> Result<String, SolverError<T>> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let cages: Vec<&Cage> = grid.constraints().iter()
+    .filter_map(|x| match x {
+      Constraint::Cage(cage) => Some(cage),
+      _ => None
+    })
+    .collect();
+  let ids: Vec<char> = CAGE_IDS.chars().collect();
+  if cages.len() > ids.len() {
+    return Err(SolverError::InvalidConstraint(format!("{} cages, only {} can be written", cages.len(), ids.len())));
+  }
+
+  let separator_line = vec!["-".repeat(2 * rows - 1); columns].join("-+-");
+
+  let mut output = String::new();
+  for row in 0..max_options {
+    if row > 0 && row % columns == 0 {                              // Sub-grid's rows i.e. swopped columns
+      output.push_str(&separator_line);
+      output.push('\n');
+    }
+
+    for column in 0..max_options {
+      if column > 0 {
+        output.push_str(if column % rows == 0 { " | " } else { " " });
+      }
+      let position = grid.position(column, row);
+      output.push(match cages.iter().position(|x| x.positions.contains(&position)) {
+        Some(cage) => ids[cage],
+        None => NO_CAGE
+      });
+    }
+    output.push('\n');
+  }
+
+  for (cage, id) in cages.iter().zip(ids.iter()) {
+    output.push_str(&format!("{}={}\n", id, cage.sum));
+  }
+
+  Ok(output)
+}
+
+// Grid of any supported size, only used for the positions of a layout's cells
+fn layout(columns: usize, rows: usize) -> Result<LargeGrid, SolverError> {
+  if columns == 0 || rows == 0 || columns * rows > SYMBOLS.len() {
+    return Err(SolverError::InvalidDimensions { columns, rows });
+  }
+
+  Ok(LargeGrid::new(columns, rows))
+}
+
+// Positions of the cells with each id of a layout written row by row, in the order the ids first appear
+fn group_ids(grid: &LargeGrid, text: &str, none: char) -> Result<Vec<(char, Vec<Position>)>, SolverError> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let ids: Vec<char> = text.chars().filter(|x| !x.is_whitespace() && !SEPARATORS.contains(x)).collect();
+
+  let expected = max_options * max_options;
+  if ids.len() != expected {
+    return Err(SolverError::InvalidInputLength { expected, actual: ids.len() });
+  }
+
+  let mut groups: Vec<(char, Vec<Position>)> = Vec::new();
+  for (index, &id) in ids.iter().enumerate() {
+    if id != none {
+      let position = grid.position(index % max_options, index / max_options);
+      match groups.iter().position(|&(x, _)| x == id) {
+        Some(group) => groups[group].1.push(position),
+        None => groups.push((id, vec![position]))
+      }
+    }
+  }
+
+  Ok(groups)
+}
+
+// Sandwich or little killer of a key e.g. "r3" or "r1c2se", if it's within the grid
+fn outside_clue(grid: &LargeGrid, key: &str, sum: usize) -> Option<Constraint> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  let index = |text: &str| match text.parse::<usize>() {
+    Ok(number) if number >= 1 && number <= max_options => Some(number - 1),
+    _ => None
+  };
+
+  if let Some(row) = key.strip_prefix('r').and_then(index) {
+    let positions = grid.get_house_positions(&House::Row(row));
+    return Some(Constraint::Sandwich(Sandwich { sum, positions }));
+  }
+  if let Some(column) = key.strip_prefix('c').and_then(index) {
+    let positions = grid.get_house_positions(&House::Column(column));
+    return Some(Constraint::Sandwich(Sandwich { sum, positions }));
+  }
+
+  let split = key.len().checked_sub(2).filter(|&x| key.is_char_boundary(x))?;
+  let (cell, direction) = key.split_at(split);
+  let (column_step, row_step): (isize, isize) = match direction {
+    "ne" => (1, -1),
+    "nw" => (-1, -1),
+    "se" => (1, 1),
+    "sw" => (-1, 1),
+    _ => return None
+  };
+  let (row, column) = cell.strip_prefix('r')?.split_once('c')?;
+  let (mut column, mut row) = (index(column)? as isize, index(row)? as isize);
+
+  let mut positions = Vec::new();
+  while column >= 0 && row >= 0 && column < max_options as isize && row < max_options as isize {
+    positions.push(grid.position(column as usize, row as usize));
+    column += column_step;
+    row += row_step;
+  }
+
+  Some(Constraint::LittleKiller(LittleKiller { sum, positions }))
+}
+
+fn symbols(text: &str) -> Vec<char> {
+  text.chars()
+    .filter(|symbol| !symbol.is_whitespace() && !SEPARATORS.contains(symbol))
+    .map(|symbol| symbol.to_ascii_uppercase())
+    .collect()
+}
+
+// Symbols of each cell - within braces or else separated by anything other than a symbol
+fn candidate_cells(text: &str) -> Vec<Vec<char>> {
+  let mut cells = Vec::new();
+  let mut cell: Vec<char> = Vec::new();
+  let mut in_braces = false;
+
+  for symbol in text.chars().map(|symbol| symbol.to_ascii_uppercase()) {
+    match symbol {
+      '{' => {
+        if !cell.is_empty() {
+          cells.push(cell.clone());
+          cell.clear();
+        }
+        in_braces = true;
+      },
+      '}' => {
+        cells.push(cell.clone());                                   // Empty braces kept to report the missing options
+        cell.clear();
+        in_braces = false;
+      },
+      _ if SYMBOLS.contains(&symbol) => cell.push(symbol),
+      _ if !in_braces && !cell.is_empty() => {
+        cells.push(cell.clone());
+        cell.clear();
+      },
+      _ => {}
+    }
+  }
+
+  if !cell.is_empty() {
+    cells.push(cell);
+  }
+
+  cells
+}
+
+fn option<T: OptionBits>(symbol: char, max_options: usize) -> Result<T, SolverError<T>> {
+  match SYMBOLS.iter().position(|&x| x == symbol) {
+    Some(index) if index < max_options => Ok(T::bit(index)),
+    _ if BLANKS.contains(&symbol) => Ok(T::ZERO),
+    _ => Err(SolverError::InvalidSymbol(symbol))
+  }
+}
+
+fn symbol<T: OptionBits>(grid: &GridOf<T>, column: usize, row: usize, blank: char) -> char {
+  let cell = grid.get_cell(&grid.position(column, row));
+  if cell.solved() {
+    cell.symbol()
+  } else {
+    blank
+  }
+}
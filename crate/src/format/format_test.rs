@@ -0,0 +1,347 @@
+#[cfg(test)]
+mod format {
+  use crate::error::SolverError;
+  use crate::format::format::{parse, dimensions};
+  use crate::grid::grid::Grid;
+
+  #[test]
+  fn it_parses_a_line() {
+    let options = parse(2, 2, "1..2.3..........").unwrap();
+
+    let grid = Grid::new(2, 2);
+    assert_eq!(options[grid.options_index(&grid.position(0, 0))], 1);
+    assert_eq!(options[grid.options_index(&grid.position(3, 0))], 2);
+    assert_eq!(options[grid.options_index(&grid.position(1, 1))], 4);
+    assert_eq!(options.iter().filter(|&&x| x > 0).count(), 3);
+  }
+
+  #[test]
+  fn it_parses_zeros_as_blanks() {
+    assert_eq!(parse(2, 2, "1002030000000000"), parse(2, 2, "1..2.3.........."));
+  }
+
+  #[test]
+  fn it_parses_an_ascii_grid() {
+    let text = concat!(
+      "1 . | . 2\n",
+      ". 3 | . .\n",
+      "----+----\n",
+      ". . | . .\n",
+      ". . | . .\n"
+    );
+
+    assert_eq!(parse(2, 2, text), parse(2, 2, "1..2.3.........."));
+  }
+
+  #[test]
+  fn it_maps_symbols_beyond_9() {
+    let mut line = String::from("9ABc");                           // Lower case accepted
+    line.push_str(&".".repeat(12 * 12 - 4));
+
+    let options = parse(3, 4, &line).unwrap();
+    let grid = Grid::new(3, 4);
+    assert_eq!(options[grid.options_index(&grid.position(0, 0))], 1 << 8);
+    assert_eq!(options[grid.options_index(&grid.position(1, 0))], 1 << 9);
+    assert_eq!(options[grid.options_index(&grid.position(2, 0))], 1 << 10);
+    assert_eq!(options[grid.options_index(&grid.position(3, 0))], 1 << 11);
+  }
+
+  #[test]
+  fn it_treats_0_as_a_symbol_in_a_6x6_grid() {
+    let mut line = String::from("0");
+    line.push_str(&".".repeat(36 * 36 - 1));
+
+    let options = parse(6, 6, &line).unwrap();
+    assert_eq!(options[0], 1 << 35);
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_symbol() {
+    assert_eq!(parse(2, 2, "1..5............"), Err(SolverError::InvalidSymbol('5')));
+  }
+
+  #[test]
+  fn it_rejects_the_wrong_number_of_symbols() {
+    assert_eq!(parse(2, 2, "1..2"), Err(SolverError::InvalidInputLength { expected: 16, actual: 4 }));
+  }
+
+  #[test]
+  fn it_works_out_the_dimensions() {
+    assert_eq!(dimensions(16), Ok((2, 2)));
+    assert_eq!(dimensions(36), Ok((2, 3)));                         // Sub-grids 3 wide by 2 high
+    assert_eq!(dimensions(81), Ok((3, 3)));
+    assert_eq!(dimensions(144), Ok((3, 4)));
+    assert_eq!(dimensions(4096), Ok((8, 8)));
+    assert_eq!(dimensions(10000), Ok((10, 10)));
+    assert_eq!(dimensions(80), Err(SolverError::UnknownLayout(80)));
+  }
+}
+
+#[cfg(test)]
+mod format_grid {
+  use crate::grid::grid::{Grid, LargeGrid};
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+
+  const PUZZLE: &str = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+  #[test]
+  fn it_loads_a_grid_from_a_line() {
+    let grid: Grid = PUZZLE.parse().unwrap();
+
+    assert_eq!(grid.dimensions(), (3, 3));
+    assert_eq!(grid.get_cell(&grid.position(0, 0)).set_method, SetMethod::Loaded);
+    assert_eq!(grid.to_line(), PUZZLE);
+  }
+
+  #[test]
+  fn it_outputs_the_solution() {
+    let mut grid: Grid = PUZZLE.parse().unwrap();
+    grid.solve_exhaustive();
+
+    assert_eq!(
+      grid.to_line(),
+      "417369825632158947958724316825437169791586432346912758289643571573291684164875293"
+    );
+  }
+
+  #[test]
+  fn it_outputs_an_ascii_grid() {
+    let grid: Grid = "1..........4....".parse().unwrap();
+
+    assert_eq!(grid.to_ascii(), concat!(
+      "1 . | . .\n",
+      ". . | . .\n",
+      "----+----\n",
+      ". . | . 4\n",
+      ". . | . .\n"
+    ));
+  }
+
+  #[test]
+  fn it_outputs_an_ascii_grid_with_rectangular_sub_grids() {
+    let grid = Grid::new(2, 3);                                     // Sub-grids 3 wide by 2 high
+
+    assert_eq!(grid.to_ascii().lines().next().unwrap(), ". . . | . . .");
+    assert_eq!(grid.to_ascii().lines().nth(2).unwrap(), "------+------");
+  }
+
+  #[test]
+  fn it_round_trips_an_ascii_grid() {
+    let grid: Grid = PUZZLE.parse().unwrap();
+    let copy: Grid = grid.to_ascii().parse().unwrap();
+
+    assert_eq!(copy.to_line(), PUZZLE);
+  }
+
+  #[test]
+  fn it_rejects_a_duplicate_given() {
+    assert!(match "11..............".parse::<Grid>() {
+      Err(SolverError::DuplicateGiven { .. }) => true,
+      _ => false
+    });
+  }
+
+  #[test]
+  fn it_loads_a_grid_beyond_64_symbols_with_more_option_bits() {
+    let puzzle = format!("Þ{}", ".".repeat(9999));                  // 10 x 10 with the 100th symbol in the top left
+    let grid: LargeGrid = puzzle.parse().unwrap();
+
+    assert_eq!(grid.dimensions(), (10, 10));
+    assert_eq!(grid.to_line(), puzzle);
+    assert_eq!(puzzle.parse::<Grid>().err(), Some(SolverError::InvalidDimensions { columns: 10, rows: 10 }));
+  }
+}
+
+#[cfg(test)]
+mod format_candidates {
+  use crate::error::SolverError;
+  use crate::format::format::{parse_candidates, load_candidates};
+  use crate::grid::grid::Grid;
+  use crate::grid::Technique;
+  use crate::cell::SetMethod;
+
+  const CANDIDATES: &str = concat!(
+    "1   34 | 234 4\n",
+    "34  2  | 1   34\n",
+    "-------+--------\n",
+    "234 1  | 4   234\n",
+    "4   34 | 23  1\n"
+  );
+
+  #[test]
+  fn it_parses_candidates_in_braces() {
+    let options = parse_candidates(2, 2, "{1}{34}{234}{4}{34}{2}{1}{34}{234}{1}{4}{234}{4}{34}{23}{1}").unwrap();
+
+    let grid = Grid::new(2, 2);
+    assert_eq!(options[grid.options_index(&grid.position(0, 0))], 0b0001);
+    assert_eq!(options[grid.options_index(&grid.position(2, 0))], 0b1110);
+    assert_eq!(options[grid.options_index(&grid.position(2, 3))], 0b0110);
+  }
+
+  #[test]
+  fn it_parses_a_candidate_board() {
+    assert_eq!(
+      parse_candidates(2, 2, CANDIDATES),
+      parse_candidates(2, 2, "{1}{34}{234}{4}{34}{2}{1}{34}{234}{1}{4}{234}{4}{34}{23}{1}")
+    );
+  }
+
+  #[test]
+  fn it_keeps_the_total_options_remaining() {
+    let grid = load_candidates(CANDIDATES).unwrap();
+
+    let cell = grid.get_cell(&grid.position(2, 0));
+    assert_eq!(cell.total_options_remaining, 3);
+    assert_eq!(cell.set_method, SetMethod::Unset);
+    assert_eq!(grid.get_cell(&grid.position(3, 0)).set_method, SetMethod::Loaded);
+  }
+
+  #[test]
+  fn it_exports_the_candidates() {
+    let grid = load_candidates(CANDIDATES).unwrap();
+
+    assert_eq!(grid.to_candidates(), CANDIDATES);
+  }
+
+  #[test]
+  fn it_finds_naked_singles_left_in_the_candidates() {
+    let mut grid = load_candidates(CANDIDATES).unwrap();
+
+    let step = grid.next_step().unwrap();
+    assert_eq!(step.technique, Technique::NakedSingle);
+
+    grid.apply_step(&step);
+    assert!(grid.get_cell(&step.cells[0]).solved());
+  }
+
+  #[test]
+  fn it_rejects_a_cell_without_candidates() {
+    assert!(match parse_candidates(2, 2, "{1}{}{234}{4}{34}{2}{1}{34}{234}{1}{4}{234}{4}{34}{23}{1}") {
+      Err(SolverError::InvalidOption { option: 0, .. }) => true,
+      _ => false
+    });
+  }
+
+  #[test]
+  fn it_rejects_the_wrong_number_of_cells() {
+    assert_eq!(parse_candidates(2, 2, "1 2 3"), Err(SolverError::InvalidInputLength { expected: 16, actual: 3 }));
+  }
+
+  #[test]
+  fn it_rejects_options_outside_the_grid() {
+    let mut grid = Grid::new(2, 2);
+
+    assert_eq!(grid.try_load_options(&vec![0b10000; 16]), Err(SolverError::InvalidOption { index: 0, option: 0b10000 }));
+  }
+}
+
+#[cfg(test)]
+mod format_cages {
+  use crate::constraint::Constraint;
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::grid::Grid;
+
+  const LAYOUT: &str = concat!(
+    "a a | b b\n",
+    "c d | e b\n",
+    "----+----\n",
+    "f d | g h\n",
+    "f i | i h\n",
+    "a=3\n",
+    "b=9\n",
+    "c=3\n",
+    "d=5\n",
+    "e=1\n",
+    "f=6\n",
+    "g=4\n",
+    "h=4\n",
+    "i=5\n"
+  );
+
+  #[test]
+  fn it_parses_cages() {
+    let cages = format::parse_cages(2, 2, "xx.. .... .... ....  x=3").unwrap();
+    let grid = Grid::new(2, 2);
+
+    assert_eq!(cages.len(), 1);
+    assert_eq!(cages[0].sum, 3);
+    assert_eq!(cages[0].positions, [grid.position(0, 0), grid.position(1, 0)]);
+  }
+
+  #[test]
+  fn it_writes_cages() {
+    let mut grid = Grid::new(2, 2);
+    for cage in format::parse_cages(2, 2, LAYOUT).unwrap().into_iter() {
+      grid.add_constraint(Constraint::Cage(cage)).unwrap();
+    }
+
+    assert_eq!(format::to_cages(&grid).unwrap(), LAYOUT);
+  }
+
+  #[test]
+  fn it_rejects_invalid_layouts() {
+    assert_eq!(
+      format::parse_cages(2, 2, "aa.. .... .... ... a=3"),
+      Err(SolverError::InvalidInputLength { expected: 16, actual: 15 })
+    );
+    assert!(matches!(format::parse_cages(2, 2, "aa.. .... .... ...."), Err(SolverError::InvalidConstraint(_))));
+    assert!(matches!(format::parse_cages(2, 2, "aa.. .... .... .... b=3"), Err(SolverError::InvalidConstraint(_))));
+    assert!(matches!(format::parse_cages(2, 2, "aa.. .... .... .... a=x"), Err(SolverError::InvalidConstraint(_))));
+  }
+}
+
+#[cfg(test)]
+mod format_houses {
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::grid::Grid;
+
+  #[test]
+  fn it_parses_houses() {
+    let houses = format::parse_houses(2, 2, "ab.. b... | .... ...a").unwrap();
+    let grid = Grid::new(2, 2);
+
+    assert_eq!(houses, vec![
+      vec![grid.position(0, 0), grid.position(3, 3)],
+      vec![grid.position(1, 0), grid.position(0, 1)]
+    ]);
+  }
+
+  #[test]
+  fn it_rejects_a_short_layout() {
+    assert_eq!(
+      format::parse_houses(2, 2, "aabb aabb ccdd ccd"),
+      Err(SolverError::InvalidInputLength { expected: 16, actual: 15 })
+    );
+  }
+}
+
+#[cfg(test)]
+mod format_outside_clues {
+  use crate::constraint::{Constraint, Sandwich, LittleKiller};
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, House};
+
+  #[test]
+  fn it_parses_outside_clues() {
+    let clues = format::parse_outside_clues(2, 2, "r2=5 c4=0 r1c3se=7 r4c2nw=3").unwrap();
+    let grid = Grid::new(2, 2);
+
+    assert_eq!(clues, vec![
+      Constraint::Sandwich(Sandwich { sum: 5, positions: grid.get_house_positions(&House::Row(1)) }),
+      Constraint::Sandwich(Sandwich { sum: 0, positions: grid.get_house_positions(&House::Column(3)) }),
+      Constraint::LittleKiller(LittleKiller { sum: 7, positions: vec![grid.position(2, 0), grid.position(3, 1)] }),
+      Constraint::LittleKiller(LittleKiller { sum: 3, positions: vec![grid.position(1, 3), grid.position(0, 2)] })
+    ]);
+  }
+
+  #[test]
+  fn it_rejects_unknown_clues() {
+    for &text in ["r5=3", "c1", "r1=x", "r1c1up=3", "r0c1se=2", "x1=3"].iter() {
+      assert!(matches!(format::parse_outside_clues(2, 2, text), Err(SolverError::InvalidConstraint(_))), "{}", text);
+    }
+  }
+}
@@ -0,0 +1,9 @@
+pub mod format;
+
+mod format_test;
+
+pub const BLANKS: [char; 2] = ['.', '0'];                           // '0' is a symbol in 6 x 6 grids
+pub const SEPARATORS: [char; 3] = ['|', '-', '+'];                  // Ignored along with whitespace
+pub const NO_CAGE: char = '.';
+pub const NO_HOUSE: char = '.';
+pub const CAGE_IDS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"; // Written in this order, any other symbol read
@@ -0,0 +1,146 @@
+use crate::cell::SetMethod;
+use crate::error::SolverError;
+use crate::generator::{PuzzleOf, Symmetry};
+use crate::grid::grid::GridOf;
+use crate::grid::Technique;
+use crate::rating::rating::rate;
+use crate::utils::bit_utils::OptionBits;
+use crate::utils::random::Random;
+
+const MAX_ATTEMPTS: usize = 20;                                     // Full grids tried for a difficulty before giving up
+const GUESSES_PER_OPTION: usize = 4;                                // Guesses allowed proving a solution unique, per option
+
+// Random full grid, then remove givens (with their symmetric cells) while the solution remains unique - with a
+//   difficulty, only while the puzzle isn't rated harder, trying another full grid until its hardest technique matches.
+//   A given is kept when uniqueness isn't proven within the guesses allowed, so puzzles of the larger grids aren't
+//   always minimal
+pub fn generate<T: OptionBits>(
+  columns: usize,
+  rows: usize,
+  seed: u64,
+  symmetry: Symmetry,
+  difficulty: Option<Technique>
+) -> Result<PuzzleOf<T>, SolverError<T>> {
+  let grid = GridOf::try_new(columns, rows)?;
+  let mut random = Random::new(seed);
+
+  for _ in 0..MAX_ATTEMPTS {
+    let solved = fill(grid.clone(), &mut random).expect("An empty grid always has a solution");
+    let solution: Vec<T> = solved.available_options_rows().concat();
+    let givens = remove_givens(&solved, &solution, &mut random, symmetry, difficulty);
+
+    let mut puzzle_grid = GridOf::new(columns, rows);
+    puzzle_grid.load_set_options(&givens);
+    let rating = rate(&puzzle_grid);
+
+    if difficulty.is_none() || rating.hardest == difficulty {
+      return Ok(PuzzleOf { columns, rows, seed, symmetry, givens, solution, rating });
+    }
+  }
+
+  Err(SolverError::DifficultyNotReached(difficulty.expect("Only retried for a difficulty")))
+}
+
+// Givens removed in a random order, each kept if the solution would no longer be unique (or too many guesses are
+//   needed to tell, as for the sparser grids of 5 x 5 and larger) or the puzzle rated harder than the difficulty
+fn remove_givens<T: OptionBits>(
+  solved: &GridOf<T>,
+  solution: &[T],
+  random: &mut Random,
+  symmetry: Symmetry,
+  difficulty: Option<Technique>
+) -> Vec<T> {
+  let (columns, rows) = solved.dimensions();
+  let mut givens = solution.to_vec();
+
+  let max_options = columns * rows;
+  let mut coordinates: Vec<(usize, usize)> = (0..max_options * max_options)
+    .map(|index| (index % max_options, index / max_options))
+    .collect();
+  random.shuffle(&mut coordinates);
+
+  for &(column, row) in coordinates.iter() {
+    let indices: Vec<usize> = symmetric_coordinates(max_options, column, row, symmetry).iter()
+      .map(|&(column, row)| solved.options_index(&solved.position(column, row)))
+      .collect();
+
+    if givens[indices[0]] != T::ZERO {                              // Not already removed with a symmetric cell
+      for &index in indices.iter() {
+        givens[index] = T::ZERO;
+      }
+
+      let mut grid = GridOf::new(columns, rows);
+      grid.load_set_options(&givens);
+      let mut keep = !grid.try_count_solutions(2, GUESSES_PER_OPTION * max_options).is_some_and(|solutions| solutions.unique());
+      if !keep && difficulty.is_some() {
+        keep = rate(&grid).hardest > difficulty;
+      }
+
+      if keep {
+        for &index in indices.iter() {                              // Restore givens
+          givens[index] = solution[index];
+        }
+      }
+    }
+  }
+
+  givens
+}
+
+// Depth first search trying the options of the most constrained cells in a random order - propagating without the
+//   subsets, too slow to repeat for every guess
+fn fill<T: OptionBits>(mut grid: GridOf<T>, random: &mut Random) -> Option<GridOf<T>> {
+  while grid.propagate() {
+  }
+
+  if !grid.is_valid() {
+    return None;
+  }
+
+  match grid.most_constrained_cell() {
+    None => Some(grid),                                             // All cells solved
+    Some((position, options)) => {
+      let mut try_options: Vec<T> = (0..T::BITS).map(T::bit).filter(|&bit| options & bit != T::ZERO).collect();
+      random.shuffle(&mut try_options);
+
+      for &try_option in try_options.iter() {
+        let mut copy = grid.clone();
+        copy.set_by_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          try_option,
+          SetMethod::Calculated
+        );
+
+        let solution = fill(copy, random);
+        if solution.is_some() {
+          return solution;
+        }
+      }
+
+      None
+    }
+  }
+}
+
+// Cell at column and row, followed by the other cells that must match it
+fn symmetric_coordinates(max_options: usize, column: usize, row: usize, symmetry: Symmetry) -> Vec<(usize, usize)> {
+  let last = max_options - 1;
+  let mut coordinates = vec![(column, row)];
+
+  let symmetric = match symmetry {
+    Symmetry::None => None,
+    Symmetry::Rotational => Some((last - column, last - row)),
+    Symmetry::Mirror => Some((last - column, row))
+  };
+
+  if let Some(other) = symmetric {
+    if other != (column, row) {                                     // Centre cell has no other cell
+      coordinates.push(other);
+    }
+  }
+
+  coordinates
+}
@@ -0,0 +1,118 @@
+#[cfg(test)]
+fn givens_match(columns: usize, rows: usize, givens: &[u64], matches: fn(usize, usize) -> (usize, usize)) -> bool {
+  use crate::grid::grid::Grid;
+
+  let grid = Grid::new(columns, rows);
+  let max_options = columns * rows;
+  let mut matched = true;
+  for row in 0..max_options {
+    for column in 0..max_options {
+      let (other_column, other_row) = matches(column, row);
+      let given = givens[grid.options_index(&grid.position(column, row))] > 0;
+      let other_given = givens[grid.options_index(&grid.position(other_column, other_row))] > 0;
+      matched &= given == other_given;
+    }
+  }
+
+  matched
+}
+
+#[cfg(test)]
+mod generator {
+  use crate::error::SolverError;
+  use crate::generator::{generator::generate, Symmetry};
+  use crate::grid::grid::Grid;
+  use crate::grid::Technique;
+  use crate::rating::rating::rate;
+
+  #[test]
+  fn it_generates_a_puzzle_with_a_unique_solution() {
+    let puzzle = generate(3, 2, 1, Symmetry::None, None).unwrap();
+
+    let mut grid = Grid::new(3, 2);
+    grid.load_set_options(&puzzle.givens);
+
+    let mut solutions = grid.count_solutions(2);
+    assert_eq!(solutions.total, 1);
+
+    let solution: Vec<u64> = solutions.grids[0].to_options().iter().map(|&x| x as u64).collect();
+    assert_eq!(solution, puzzle.solution);
+    assert!(puzzle.givens.iter().any(|&x| x == 0));                 // Givens removed
+    assert!(puzzle.givens.iter().zip(puzzle.solution.iter()).all(|(&given, &option)| given == 0 || given == option));
+  }
+
+  #[test]
+  fn it_rates_the_puzzle() {
+    let puzzle = generate(3, 2, 1, Symmetry::None, None).unwrap();
+
+    let mut grid = Grid::new(3, 2);
+    grid.load_set_options(&puzzle.givens);
+    assert_eq!(puzzle.rating, rate(&grid));
+    assert!(puzzle.rating.solved);
+  }
+
+  #[test]
+  fn it_generates_the_same_puzzle_for_the_same_seed() {
+    let puzzle = generate::<u64>(2, 2, 42, Symmetry::None, None).unwrap();
+
+    assert_eq!(generate(2, 2, 42, Symmetry::None, None).unwrap(), puzzle);
+    assert_ne!(generate::<u64>(2, 2, 43, Symmetry::None, None).unwrap().givens, puzzle.givens);
+  }
+
+  #[test]
+  fn it_generates_a_rotationally_symmetric_puzzle() {
+    let puzzle = generate(2, 3, 7, Symmetry::Rotational, None).unwrap();
+
+    assert!(super::givens_match(2, 3, &puzzle.givens, |column, row| (5 - column, 5 - row)));
+  }
+
+  #[test]
+  fn it_generates_a_mirrored_puzzle() {
+    let puzzle = generate(2, 2, 7, Symmetry::Mirror, None).unwrap();
+
+    assert!(super::givens_match(2, 2, &puzzle.givens, |column, row| (3 - column, row)));
+  }
+
+  #[test]
+  fn it_generates_a_3x3_puzzle() {
+    let puzzle = generate(3, 3, 2020, Symmetry::Rotational, None).unwrap();
+
+    let mut grid = Grid::new(3, 3);
+    grid.load_set_options(&puzzle.givens);
+    assert!(grid.count_solutions(2).unique());
+  }
+
+  #[test]
+  fn it_generates_a_4x4_puzzle() {
+    let puzzle = generate(4, 4, 1, Symmetry::None, None).unwrap();  // Subsets not looked for while guessing
+
+    let mut grid = Grid::new(4, 4);
+    grid.load_set_options(&puzzle.givens);
+    assert!(puzzle.givens.iter().any(|&x| x == 0));
+    assert!(grid.solve_exhaustive());
+    assert_eq!(grid.to_options().iter().map(|&x| x as u64).collect::<Vec<u64>>(), puzzle.solution);
+  }
+
+  #[test]
+  fn it_generates_a_puzzle_for_a_difficulty() {
+    let puzzle = generate(3, 3, 1, Symmetry::Rotational, Some(Technique::HiddenSingle)).unwrap();
+
+    let mut grid = Grid::new(3, 3);
+    grid.load_set_options(&puzzle.givens);
+    assert!(grid.count_solutions(2).unique());
+    assert_eq!(puzzle.rating.hardest, Some(Technique::HiddenSingle));
+  }
+
+  #[test]
+  fn it_gives_up_on_an_unreachable_difficulty() {
+    assert_eq!(
+      generate::<u64>(2, 2, 1, Symmetry::None, Some(Technique::Trial)),   // Singles always enough for 4 x 4
+      Err(SolverError::DifficultyNotReached(Technique::Trial))
+    );
+  }
+
+  #[test]
+  fn it_rejects_unsupported_dimensions() {
+    assert!(generate::<u64>(9, 8, 1, Symmetry::None, None).is_err());      // More than 64 symbols
+  }
+}
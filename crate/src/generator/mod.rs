@@ -0,0 +1,32 @@
+use wasm_bindgen::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::rating::Rating;
+use crate::utils::bit_utils::OptionBits;
+
+pub mod generator;
+
+mod generator_test;
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Symmetry {
+  None,
+  Rotational,                                                       // 180 degrees about the centre
+  Mirror                                                            // Left to right about the middle column
+}
+
+pub type Puzzle = PuzzleOf<u64>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: OptionBits")]
+pub struct PuzzleOf<T: OptionBits> {
+  pub columns: usize,
+  pub rows: usize,
+  pub seed: u64,
+  pub symmetry: Symmetry,
+  pub givens: Vec<T>,                                               // Set options as loaded by Grid::load_set_options
+  pub solution: Vec<T>,
+  pub rating: Rating
+}
@@ -1,24 +1,52 @@
 use std::fmt::{self, Display};
-use std::collections::HashSet;
 
 use crate::utils::combinations::Combinations;
-use crate::utils::bit_utils::{number_of_bits_set, bitwise_or, only_option, containing_bit_index};
-use crate::cell::{cell::Cell, SetMethod};
-use crate::sub_grid::{sub_grid::SubGrid, BitOption};
-use crate::grid::CellOptions;
+use crate::utils::bit_utils::{OptionBits, number_of_bits_set, bitwise_or, only_option, containing_bit_index, power_of_2_bit_positions, option_words};
+use crate::cell::{cell::CellOf, SetMethod, CellState, SYMBOLS};
+use crate::sub_grid::{sub_grid::SubGridOf, BitOption};
+use crate::grid::{
+  CellOptions, Position, House, Contradiction, Solutions, Technique, OptionChange, Step, TraceOf, TraceAction, TraceEntry,
+  Reason, Cause, JsonGrid, JsonGridRow, UserAction, CellChange, Move, HistoryOf, Mistake, Variant, Rule, Edge, EdgeClue
+};
+use crate::error::SolverError;
 use crate::utils::array_utils;
+use crate::format::format;
+use crate::constraint::{constraint, Constraint};
+
+const MAX_SUBSET_COMBINATIONS: u128 = 12870;                        // C(16, 8) i.e. every subset up to 16 x 16 grids
+const ORTHOGONAL: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];  // Moves to the adjacent cells
+
+// Grid with up to 64 symbols e.g. 8 x 8 - see GridOf for the option bits of smaller or larger grids
+pub type Grid = GridOf<u64>;
+
+// Grid with up to 16 symbols e.g. 4 x 4, the fastest for the usual 9 x 9 grids
+pub type SmallGrid = GridOf<u16>;
+
+// Grid with up to 100 symbols e.g. 10 x 10, as many as there are SYMBOLS
+pub type LargeGrid = GridOf<u128>;
 
 #[derive(Debug, Clone)]
-pub struct Grid {
+pub struct GridOf<T: OptionBits> {
   max_columns: usize,
   max_rows: usize,
   max_options: usize,
   combinations: Combinations,
+  trace: Option<TraceOf<T>>,                                        // Only recorded once enabled
+  history: HistoryOf<T>,
+  constraints: Vec<Constraint>,                                     // Kept when reset i.e. part of the puzzle
+  constraint_combinations: Vec<Vec<Vec<T>>>,                        // Each constraint's, worked out when added
+  variant: Variant,                                                 // Kept when reset
+  regions: Vec<Vec<Position>>,                                      // Jigsaw regions replacing the sub-grids, if any
+  extra_houses: Vec<Vec<Position>>,                                 // e.g. Windoku windows
+  rules: Vec<Rule>,                                                 // Kept when reset
+  edges: Vec<Edge>,                                                 // Kept when reset
+  negative_clues: Vec<EdgeClue>,                                    // Clues whose absence is also a clue
+  single_steps: bool,                                               // Nothing cascaded when striking out, for rating
   
-  sub_grids: Vec<Vec<SubGrid>>                                      // use get(column, row) -> returns sub-grids[row][column]
+  sub_grids: Vec<Vec<SubGridOf<T>>>                                 // use get(column, row) -> returns sub-grids[row][column]
 }
 
-impl Display for Grid {
+impl<T: OptionBits> Display for GridOf<T> {
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
     let options_rows = self.available_options_rows();
     let transposed_rows = array_utils::transpose_rows(self.max_columns, &options_rows);
@@ -32,14 +60,14 @@ impl Display for Grid {
   }
 }
 
-impl Grid {
+impl<T: OptionBits> GridOf<T> {
   pub fn new(max_columns: usize, max_rows: usize) -> Self {
-    let mut sub_grids: Vec<Vec<SubGrid>> = Vec::with_capacity(max_rows);
+    let mut sub_grids: Vec<Vec<SubGridOf<T>>> = Vec::with_capacity(max_rows);
 
     for row in 0..max_rows {
       sub_grids.push(Vec::with_capacity(max_columns));
       for column in 0..max_columns {
-        sub_grids[row].push(SubGrid::new(max_rows, max_columns, column, row));  // max columns and rows swopped
+        sub_grids[row].push(SubGridOf::new(max_rows, max_columns, column, row));  // max columns and rows swopped
       }
     }
   
@@ -50,24 +78,218 @@ impl Grid {
       max_rows,
       max_options,
       combinations: Combinations::new(max_options),
+      trace: None,
+      history: HistoryOf::new(),
+      constraints: Vec::new(),
+      constraint_combinations: Vec::new(),
+      variant: Variant::Standard,
+      regions: Vec::new(),
+      extra_houses: Vec::new(),
+      rules: Vec::new(),
+      edges: Vec::new(),
+      negative_clues: Vec::new(),
+      single_steps: false,
       sub_grids
     }
   }
 
+  pub fn try_new(max_columns: usize, max_rows: usize) -> Result<Self, SolverError<T>> {
+    if max_columns == 0 || max_rows == 0 || max_columns * max_rows > SYMBOLS.len().min(T::BITS) {
+      return Err(SolverError::InvalidDimensions { columns: max_columns, rows: max_rows });
+    }
+
+    Ok(Self::new(max_columns, max_rows))
+  }
+
   pub fn reset(&mut self) {
     for row in 0..self.max_rows {
       for column in 0..self.max_columns {
         self.sub_grids[row][column].reset();
       }
     }
+
+    if self.trace.is_some() {
+      self.trace = Some(TraceOf::new());
+    }
+    self.history = HistoryOf::new();
+  }
+
+  // Record every option set, struck out or removed from now on i.e. while solving
+  pub fn enable_trace(&mut self) {
+    self.trace = Some(TraceOf::new());
+  }
+
+  pub fn trace(&self) -> Option<&TraceOf<T>> {
+    self.trace.as_ref()
+  }
+
+  pub fn take_trace(&mut self) -> Option<TraceOf<T>> {
+    self.trace.take()
+  }
+
+  // Grid's columns and rows of sub-grids, as passed to new
+  pub fn dimensions(&self) -> (usize, usize) {
+    (self.max_columns, self.max_rows)
+  }
+
+  // Rule on top of the columns, rows and sub-grids e.g. a killer cage - applied when next simplified
+  pub fn add_constraint(&mut self, constraint: Constraint) -> Result<(), SolverError<T>> {
+    constraint::validate(self, &constraint)?;
+    self.constraint_combinations.push(constraint::combinations(self, &constraint));
+    self.constraints.push(constraint);
+
+    Ok(())
+  }
+
+  pub fn constraints(&self) -> &[Constraint] {
+    &self.constraints
+  }
+
+  // Houses added by the variant e.g. the diagonals - set before loading the puzzle, as cells already solved aren't struck
+  //   out again
+  pub fn set_variant(&mut self, variant: Variant) {
+    self.variant = variant;
+  }
+
+  pub fn variant(&self) -> Variant {
+    self.variant
+  }
+
+  // Jigsaw regions covering the grid, replacing the rectangular sub-grids - set before loading the puzzle
+  pub fn set_regions(&mut self, regions: Vec<Vec<Position>>) -> Result<(), SolverError<T>> {
+    if regions.len() != self.max_options {
+      return Err(SolverError::InvalidHouse(format!("{} regions, expected {}", regions.len(), self.max_options)));
+    }
+
+    let mut covered = vec![false; self.max_options * self.max_options];
+    for positions in regions.iter() {
+      self.validate_house(positions)?;
+      for position in positions.iter() {
+        covered[self.options_index(position)] = true;
+      }
+    }
+
+    if covered.iter().any(|&x| !x) {                                // Only if overlapping, as each region is full
+      return Err(SolverError::InvalidHouse("regions overlap".to_string()));
+    }
+
+    self.regions = regions;
+    Ok(())
+  }
+
+  pub fn regions(&self) -> &[Vec<Position>] {
+    &self.regions
+  }
+
+  // House on top of the columns, rows and sub-grids each symbol must appear in once e.g. a Windoku window - added before
+  //   loading the puzzle
+  pub fn add_house(&mut self, positions: Vec<Position>) -> Result<(), SolverError<T>> {
+    self.validate_house(&positions)?;
+    self.extra_houses.push(positions);
+
+    Ok(())
+  }
+
+  pub fn extra_houses(&self) -> &[Vec<Position>] {
+    &self.extra_houses
+  }
+
+  // Rule between cells a move apart e.g. anti-knight, applied when striking out - added before loading the puzzle
+  pub fn add_rule(&mut self, rule: Rule) {
+    if !self.rules.contains(&rule) {
+      self.rules.push(rule);
+    }
+  }
+
+  pub fn rules(&self) -> &[Rule] {
+    &self.rules
+  }
+
+  // Clue between orthogonally adjacent cells e.g. a Kropki dot, applied when striking out and simplifying - added before
+  //   loading the puzzle
+  pub fn add_edge(&mut self, edge: Edge) -> Result<(), SolverError<T>> {
+    for position in [edge.first, edge.second].iter() {
+      if !self.valid_position(position) {
+        return Err(SolverError::InvalidPosition(*position));
+      }
+    }
+
+    if !self.get_neighbours(&edge.first, &ORTHOGONAL).contains(&edge.second) {
+      let error = format!("{} between {} and {}, which aren't adjacent", edge.clue, edge.first, edge.second);
+      return Err(SolverError::InvalidConstraint(error));
+    }
+
+    self.edges.push(edge);
+    Ok(())
+  }
+
+  pub fn edges(&self) -> &[Edge] {
+    &self.edges
+  }
+
+  // Adjacent cells without a clue of the kind (a dot or XV) don't match the clue e.g. no white dot means not consecutive
+  pub fn add_negative_clue(&mut self, clue: EdgeClue) {
+    if !self.negative_clues.contains(&clue) {
+      self.negative_clues.push(clue);
+    }
+  }
+
+  pub fn negative_clues(&self) -> &[EdgeClue] {
+    &self.negative_clues
+  }
+
+  // Row by row e.g. "4.....8.5.3...." with '.' for unsolved cells
+  pub fn to_line(&self) -> String {
+    format::to_line(self, '.')
+  }
+
+  pub fn to_ascii(&self) -> String {
+    format::to_ascii(self)
+  }
+
+  // Remaining options of every cell as a board with the sub-grids separated e.g. "4 126 3 | ..."
+  pub fn to_candidates(&self) -> String {
+    format::to_candidates(self)
+  }
+
+  pub fn to_json(&self) -> JsonGrid {
+    JsonGrid {
+      rows: self.sub_grids.iter()
+        .map(|sub_grids| JsonGridRow { columns: sub_grids.iter().map(|sub_grid| sub_grid.to_json()).collect() })
+        .collect()
+    }
   }
 
-  pub fn get(&mut self, column: usize, row: usize) -> &SubGrid {
+  // Restore a snapshot as is i.e. without striking out - the grid is unchanged if the snapshot doesn't fit
+  pub fn set_json(&mut self, json: &JsonGrid) -> Result<(), SolverError<T>> {
+    if json.rows.len() != self.max_rows || json.rows.iter().any(|x| x.columns.len() != self.max_columns) {
+      return Err(SolverError::InvalidJson(
+        format!("Expected {} x {} sub-grids", self.max_columns, self.max_rows)
+      ));
+    }
+
+    let mut sub_grids = self.sub_grids.clone();
+    for (sub_grids_row, json_row) in sub_grids.iter_mut().zip(json.rows.iter()) {
+      for (sub_grid, json_sub_grid) in sub_grids_row.iter_mut().zip(json_row.columns.iter()) {
+        sub_grid.set_json(json_sub_grid)?;
+      }
+    }
+
+    self.sub_grids = sub_grids;
+    Ok(())
+  }
+
+  // Killer cages with the id of each cell's cage, followed by the sums e.g. "a a b | ...  a=3"
+  pub fn to_cages(&self) -> Result<String, SolverError<T>> {
+    format::to_cages(self)
+  }
+
+  pub fn get(&mut self, column: usize, row: usize) -> &SubGridOf<T> {
     // sub-grids called by [column, row] but accessed by [row][column] for efficiency
     &self.sub_grids[row][column]
   } 
   
-  pub fn compare(&self, items: &Vec<Vec<SubGrid>>) -> bool {
+  pub fn compare(&self, items: &Vec<Vec<SubGridOf<T>>>) -> bool {
     let mut equal = true;
     let mut row = self.max_rows;
     while equal && row > 0 {
@@ -83,7 +305,7 @@ impl Grid {
     equal
   }
 
-  pub fn available_options_rows(&self) -> Vec<Vec<u64>> {
+  pub fn available_options_rows(&self) -> Vec<Vec<T>> {
     let mut options_rows = Vec::with_capacity(self.max_options);
     for row in 0..self.max_rows {
       for column in 0..self.max_columns {
@@ -106,6 +328,119 @@ impl Grid {
     self.solved()
   }
 
+  pub fn try_solve(&mut self) -> Result<bool, SolverError<T>> {
+    let solved = self.solve();
+
+    if let Some(contradiction) = self.check_consistency() {
+      return Err(SolverError::Contradiction(contradiction));
+    }
+
+    Ok(solved)
+  }
+
+  // Depth first search over the options of the most constrained cells, on top of the strike out propagation, until
+  //   solved or every option tried i.e. no solution
+  pub fn solve_exhaustive(&mut self) -> bool {
+    let mut solutions = Solutions::new();
+    self.search_root().search(1, &mut solutions);
+
+    match solutions.grids.pop() {
+      Some(solution) => {
+        *self = solution;
+        true
+      },
+      None => false
+    }
+  }
+
+  // Search for up to limit solutions i.e. a limit of 2 is enough to check if the solution is unique
+  pub fn count_solutions(&self, limit: usize) -> Solutions<T> {
+    let mut solutions = Solutions::new();
+    if limit > 0 {
+      self.search_root().search(limit, &mut solutions);
+    }
+
+    solutions
+  }
+
+  // Solutions found by guessing on top of propagate alone, without the subsets - None if giving up after max_guesses
+  //   i.e. quicker for the sparse grids of a generator, at the cost of not always knowing
+  pub fn try_count_solutions(&self, limit: usize, max_guesses: usize) -> Option<Solutions<T>> {
+    let mut solutions = Solutions { max_guesses, ..Solutions::new() };
+    if limit > 0 {
+      self.clone().search(limit, &mut solutions);
+    }
+
+    if solutions.total < limit && solutions.guesses >= max_guesses {
+      return None;
+    }
+
+    Some(solutions)
+  }
+
+  // Copy simplified before searching - the subsets only looked for once, too slow to repeat for every guess
+  fn search_root(&self) -> Self {
+    let mut root = self.clone();
+    while root.simplify() {
+    }
+
+    root
+  }
+
+  fn search(mut self, limit: usize, solutions: &mut Solutions<T>) {
+    while self.propagate() {
+    }
+
+    if !self.is_valid() {
+      return;
+    }
+
+    match self.most_constrained_cell() {
+      None => solutions.add(self),                                  // All cells solved
+      Some((position, options)) => {
+        let mut remaining_options = options;
+        while remaining_options != T::ZERO && solutions.total < limit && solutions.guesses < solutions.max_guesses {
+          let try_option = remaining_options & !(remaining_options - T::ONE);  // lowest set bit value
+          solutions.guesses += 1;
+
+          let mut copy = self.clone();                              // Keep the trace leading to each solution
+          let cause = copy.trace_cause(None, Reason::Guess);
+          copy.set_by_option(
+            position.sub_grid_column,
+            position.sub_grid_row,
+            position.cell_column,
+            position.cell_row,
+            try_option,
+            SetMethod::Calculated
+          );
+          copy.restore_cause(cause);
+          copy.search(limit, solutions);
+
+          remaining_options -= try_option;                          // remove tried option
+        }
+      }
+    }
+  }
+
+  // Unsolved cell with the fewest options remaining
+  pub fn most_constrained_cell(&self) -> Option<(Position, T)> {
+    let mut most_constrained = None;
+    let mut fewest_options = self.max_options + 1;
+
+    for row in 0..self.max_options {
+      for column in 0..self.max_options {
+        let position = self.position(column, row);
+        let cell = self.get_cell(&position);
+        if cell.total_options_remaining > 1 && cell.total_options_remaining < fewest_options {
+          fewest_options = cell.total_options_remaining;
+          most_constrained = Some((position, cell.options));
+        }
+      }
+    }
+
+    most_constrained
+  }
+
   pub fn solved(&self) -> bool {
     let mut solved = true;
 
@@ -126,31 +461,48 @@ impl Grid {
     let mut only_option_found = false;
 
     // Check/remove only options in columns/rows/sub-grids and mulitipe options limited to a certain number of
-    //   related cells i.e. if 2 cells in a row can only contain 1 or 2 => remove from other cells in row
-    while self.remove_only_options() || self.check_limited_options() {
+    //   related cells i.e. if 2 cells in a row can only contain 1 or 2 => remove from other cells in row, then options
+    //   limited to where the other houses meet
+    while self.remove_only_options() || self.check_limited_options() || self.check_house_intersections() ||
+      self.apply_edges() || self.apply_constraints() {
       only_option_found = true;
     }
 
     only_option_found
   }
 
+  // Only options and the clues' allowed options, cheap enough to repeat after each guess on top of the strike out
+  pub fn propagate(&mut self) -> bool {
+    self.remove_only_options() || self.apply_edges() || self.apply_constraints()
+  }
+
   pub fn remove_option(
     &mut self,
     sub_grid_column: usize,
     sub_grid_row: usize,
     cell_column: usize,
     cell_row: usize,
-    option: u64
+    option: T
   ) -> bool {
-    if self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column].remove_option(option) {
+    let cell_options = self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column].options;
+    let last_option_found = self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column].remove_option(option);
+
+    if self.trace.is_some() && cell_options != self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column].options {
+      let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+      self.record(TraceAction::RemoveOption, position, option, vec![OptionChange { position, options: option }]);
+    }
+
+    if last_option_found {
       // Check if last option left
-      self.strike_out(
-        sub_grid_column,
-        sub_grid_row,
-        cell_column,
-        cell_row,
-        self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column].options
-      );
+      if !self.single_steps {                                       // Left for naked_single_step
+        self.strike_out(
+          sub_grid_column,
+          sub_grid_row,
+          cell_column,
+          cell_row,
+          self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column].options
+        );
+      }
 
       return true;
     }
@@ -158,7 +510,135 @@ impl Grid {
     false
   }
 
-  pub fn load_set_options(&mut self, options: &Vec<u64>) {
+  pub fn try_remove_option(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option: T
+  ) -> Result<bool, SolverError<T>> {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    self.check_option(&position, option)?;
+
+    Ok(self.remove_option(sub_grid_column, sub_grid_row, cell_column, cell_row, option))
+  }
+
+  // Add or remove the user's own mark without changing the candidates i.e. no striking out
+  pub fn toggle_pencil_mark(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option: T
+  ) -> Result<(), SolverError<T>> {
+    self.check_option(&Position { sub_grid_column, sub_grid_row, cell_column, cell_row }, option)?;
+
+    let index = power_of_2_bit_positions(option);
+    self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column]
+      .toggle_pencil_mark_at_position(index % self.max_columns, index / self.max_columns);
+    Ok(())
+  }
+
+  pub fn toggle_highlight(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option: T
+  ) -> Result<(), SolverError<T>> {
+    self.check_option(&Position { sub_grid_column, sub_grid_row, cell_column, cell_row }, option)?;
+
+    let index = power_of_2_bit_positions(option);
+    self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column]
+      .toggle_highlight_option_at_position(index % self.max_columns, index / self.max_columns);
+    Ok(())
+  }
+
+  // Pencil marks of each cell that are not among its candidates
+  pub fn pencil_mark_mistakes(&self) -> Vec<OptionChange<T>> {
+    let mut mistakes = Vec::new();
+
+    for sub_grid_row in 0..self.max_rows {
+      for sub_grid_column in 0..self.max_columns {
+        for cell_row in 0..self.max_columns {                       // dimensions columns & rows swopped
+          for cell_column in 0..self.max_rows {
+            let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+            let options = self.get_cell(&position).pencil_mark_mistakes();
+            if options != T::ZERO {
+              mistakes.push(OptionChange { position, options });
+            }
+          }
+        }
+      }
+    }
+
+    mistakes
+  }
+
+  // Next deduction without changing the grid, trying the simplest techniques first - None if the grid is solved, invalid
+  //   or no deduction can be made
+  pub fn next_step(&self) -> Option<Step<T>> {
+    if self.solved() || !self.is_valid() {
+      return None;
+    }
+
+    self.naked_single_step()
+      .or_else(|| self.hidden_single_step())
+      .or_else(|| self.subset_step())
+      .or_else(|| self.box_line_step())
+      .or_else(|| self.house_intersection_step())
+      .or_else(|| self.trial_step())
+  }
+
+  pub fn apply_step(&mut self, step: &Step<T>) {
+    let reason = match (step.technique, step.house) {
+      (Technique::HiddenSingle, Some(house)) => Reason::OnlyOption(house),
+      (Technique::NakedSubset, Some(house)) | (Technique::HiddenSubset, Some(house)) => Reason::LimitedOptions(house),
+      (Technique::BoxLineReduction, Some(house)) => Reason::BoxLine(house),
+      (Technique::Trial, _) => Reason::Trial,
+      _ => Reason::LastOption
+    };
+    let cause = self.trace_cause(Some(step.technique), reason);
+
+    for change in step.set.iter() {
+      let position = change.position;
+      if self.get_cell(&position).solved() {                        // Naked single i.e. only strike out from other cells
+        self.strike_out(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          change.options
+        );
+      } else {
+        self.set_by_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          change.options,
+          SetMethod::Calculated
+        );
+      }
+    }
+
+    for change in step.removed.iter() {
+      let position = change.position;
+      let mut remaining_options = change.options;
+      while remaining_options != T::ZERO {
+        let option = remaining_options & !(remaining_options - T::ONE); // lowest set bit value
+        self.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+        remaining_options -= option;
+      }
+    }
+
+    self.restore_cause(cause);
+  }
+
+  pub fn load_set_options(&mut self, options: &Vec<T>) {
     let grouped = array_utils::group_by_root(options);
 
     for sub_grid_row in 0..self.max_rows {
@@ -167,8 +647,8 @@ impl Grid {
         for cell_row in 0..self.max_columns {                // dimensions columns & rows swopped
           for cell_column in 0..self.max_rows {
             let option = sub_grid_options[cell_row * self.max_rows + cell_column];
-            if option > 0 {
-              self.set_by_option(sub_grid_column, sub_grid_row, cell_column, cell_row, option, SetMethod::Loaded);
+            if option != T::ZERO {
+              self.load_given(&Position { sub_grid_column, sub_grid_row, cell_column, cell_row }, option);
             }
           }
         }
@@ -176,28 +656,324 @@ impl Grid {
     }
   }
 
-  fn is_valid(&self) -> bool {
-    // Check columns and rows contain all options and no set cell duplicted
-    self.matrix_valid(self.get_transposed_cells_matrix()) && self.matrix_valid(self.get_cells_matrix())
+  // Options of every cell in the same order as the set options i.e. candidates already removed, without striking out
+  pub fn load_options(&mut self, options: &Vec<T>) {
+    let grouped = array_utils::group_by_root(options);
+
+    for sub_grid_row in 0..self.max_rows {
+      for sub_grid_column in 0..self.max_columns {
+        let sub_grid_options = &grouped[sub_grid_row * self.max_columns + sub_grid_column];
+        for cell_row in 0..self.max_columns {                       // dimensions columns & rows swopped
+          for cell_column in 0..self.max_rows {
+            self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column]
+              .load_options(sub_grid_options[cell_row * self.max_rows + cell_column]);
+          }
+        }
+      }
+    }
   }
 
-  fn matrix_valid(&self, matrix: Vec<Vec<&Cell>>) -> bool {
-    let mut valid = true;
+  pub fn try_load_options(&mut self, options: &Vec<T>) -> Result<(), SolverError<T>> {
+    let expected = self.max_options * self.max_options;
+    if options.len() != expected {
+      return Err(SolverError::InvalidInputLength { expected, actual: options.len() });
+    }
 
-    let mut index = self.max_options;
-		while valid && index > 0 {
-      index -= 1;
+    let all_options = T::mask(self.max_options);
+    for (index, &option) in options.iter().enumerate() {
+      if option == T::ZERO || option & !all_options != T::ZERO {    // Every cell needs at least one of the grid's options
+        return Err(SolverError::InvalidOption { index, option });
+      }
+    }
+
+    self.load_options(options);
+    Ok(())
+  }
+
+  // Check the number of options and that each is either unset (0) or a single option
+  pub fn validate_set_options(&self, options: &[T]) -> Result<(), SolverError<T>> {
+    let expected = self.max_options * self.max_options;
+    if options.len() != expected {
+      return Err(SolverError::InvalidInputLength { expected, actual: options.len() });
+    }
 
-			let set_options = distinct_set_options(&matrix[index]);       // Get unique set cells
-			let unset_options = unset_options(&matrix[index]);
+    let all_options = T::mask(self.max_options);
+    for (index, &option) in options.iter().enumerate() {
+      if option != T::ZERO && option & ((option - T::ONE) | !all_options) != T::ZERO {  // Not 0 or a single option within the options
+        return Err(SolverError::InvalidOption { index, option });
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn try_load_set_options(&mut self, options: &Vec<T>) -> Result<(), SolverError<T>> {
+    self.validate_set_options(options)?;
+
+    let grouped = array_utils::group_by_root(options);
+
+    for sub_grid_row in 0..self.max_rows {
+      for sub_grid_column in 0..self.max_columns {
+        let sub_grid_options = &grouped[sub_grid_row * self.max_columns + sub_grid_column];
+        for cell_row in 0..self.max_columns {                       // dimensions columns & rows swopped
+          for cell_column in 0..self.max_rows {
+            let option = sub_grid_options[cell_row * self.max_rows + cell_column];
+            if option != T::ZERO {
+              let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+              if !self.get_cell(&position).contains_option(option) {
+                return Err(self.set_option_error(&position, option));
+              }
 
-      valid = set_options.len() + unset_options.len() == self.max_options &&  // Ensures set_options do not contain duplicates
-        (bitwise_or(&set_options) | bitwise_or(&unset_options)) == (1 << self.max_options) - 1; // total set_options | unset_options must contain all the options
+              self.load_given(&position, option);
+            }
+          }
+        }
+      }
     }
 
-		valid
+    Ok(())
   }
-  
+
+  pub fn is_valid(&self) -> bool {
+    self.check_consistency().is_none()
+  }
+
+  // First contradiction found: a cell without options, an option set more than once in a house (column, row, sub-grid,
+  //   region, diagonal or extra house), or an option with no cell left in a house
+  pub fn check_consistency(&self) -> Option<Contradiction<T>> {
+    for row in 0..self.max_options {
+      for column in 0..self.max_options {
+        let position = self.position(column, row);
+        if self.get_cell(&position).options == T::ZERO {
+          return Some(Contradiction::NoOptions(position));
+        }
+      }
+    }
+
+    let houses = self.get_houses();
+
+    for (house, positions) in houses.iter() {
+      let mut set_options = T::ZERO;
+      for position in positions.iter() {
+        let cell = self.get_cell(position);
+        if cell.solved() {
+          if set_options & cell.options != T::ZERO {
+            let option = cell.options;
+            return Some(Contradiction::DuplicateOption {
+              house: *house,
+              option,
+              positions: positions.iter()
+                .filter(|&x| self.get_cell(x).solved() && self.get_cell(x).options == option)
+                .copied()
+                .collect()
+            });
+          }
+          set_options |= cell.options;
+        }
+      }
+    }
+
+    let all_options = T::mask(self.max_options);
+    for (house, positions) in houses.iter() {
+      let available_options = positions.iter().fold(T::ZERO, |options, position| options | self.get_cell(position).options);
+      let missing_options = all_options & !available_options;
+      if missing_options != T::ZERO {
+        return Some(Contradiction::MissingOption {
+          house: *house,
+          option: missing_options & !(missing_options - T::ONE),    // lowest missing option
+          positions: positions.clone()
+        });
+      }
+    }
+
+    for &rule in self.rules.iter() {
+      for row in 0..self.max_options {
+        for column in 0..self.max_options {
+          let position = self.position(column, row);
+          let cell = self.get_cell(&position);
+          if cell.solved() {
+            let options = self.rule_options(rule, cell.options);
+            let broken = self.get_rule_neighbours(&position, rule).into_iter()
+              .find(|neighbour| self.get_cell(neighbour).solved() && self.get_cell(neighbour).options & options != T::ZERO);
+
+            if let Some(neighbour) = broken {
+              return Some(Contradiction::BrokenRule { rule, positions: vec![position, neighbour] });
+            }
+          }
+        }
+      }
+    }
+
+    for edge in self.edges.iter() {
+      let (first, second) = (self.get_cell(&edge.first), self.get_cell(&edge.second));
+      if first.solved() && second.solved() && edge.clue.options(first.options, self.max_options) & second.options == T::ZERO {
+        return Some(Contradiction::BrokenClue { clue: edge.clue, negative: false, positions: vec![edge.first, edge.second] });
+      }
+    }
+
+    for &clue in self.negative_clues.iter() {
+      for row in 0..self.max_options {
+        for column in 0..self.max_options {
+          let position = self.position(column, row);
+          let options = self.get_cell(&position).options;
+          if self.get_cell(&position).solved() {
+            let broken = self.get_neighbours(&position, &ORTHOGONAL).into_iter().find(|neighbour| {
+              let cell = self.get_cell(neighbour);
+              cell.solved() && self.negative_applies(clue, &position, neighbour) &&
+                clue.options(options, self.max_options) & cell.options != T::ZERO
+            });
+
+            if let Some(neighbour) = broken {
+              return Some(Contradiction::BrokenClue { clue, negative: true, positions: vec![position, neighbour] });
+            }
+          }
+        }
+      }
+    }
+
+    for (index, item) in self.constraints.iter().enumerate() {
+      if let Some(positions) = constraint::violation(self, item, &self.constraint_combinations[index]) {
+        return Some(Contradiction::BrokenConstraint { constraint: index, positions });
+      }
+    }
+
+    None
+  }
+
+  // Positions of the cells in each column, row and sub-grid (or jigsaw region), followed by the diagonals of the variant
+  //   and the extra houses
+  pub fn get_houses(&self) -> Vec<(House, Vec<Position>)> {
+    let mut houses = Vec::with_capacity(3 * self.max_options + 2 + self.extra_houses.len());
+
+    for column in 0..self.max_options {
+      houses.push(House::Column(column));
+    }
+
+    for row in 0..self.max_options {
+      houses.push(House::Row(row));
+    }
+
+    if self.regions.is_empty() {
+      for sub_grid_row in 0..self.max_rows {
+        for sub_grid_column in 0..self.max_columns {
+          houses.push(House::SubGrid(sub_grid_column, sub_grid_row));
+        }
+      }
+    }
+
+    houses.extend(self.get_other_houses());
+
+    houses.into_iter().map(|house| (house, self.get_house_positions(&house))).collect()
+  }
+
+  pub fn get_house_positions(&self, house: &House) -> Vec<Position> {
+    match *house {
+      House::Column(column) => (0..self.max_options).map(|row| self.position(column, row)).collect(),
+      House::Row(row) => (0..self.max_options).map(|column| self.position(column, row)).collect(),
+      House::SubGrid(sub_grid_column, sub_grid_row) => {
+        let mut positions = Vec::with_capacity(self.max_options);
+        for cell_row in 0..self.max_columns {                       // dimensions columns & rows swopped
+          for cell_column in 0..self.max_rows {
+            positions.push(Position { sub_grid_column, sub_grid_row, cell_column, cell_row });
+          }
+        }
+
+        positions
+      },
+      House::Diagonal(0) => (0..self.max_options).map(|index| self.position(index, index)).collect(),
+      House::Diagonal(_) => (0..self.max_options).map(|index| self.position(self.max_options - 1 - index, index)).collect(),
+      House::Region(region) => self.regions[region].clone(),
+      House::Extra(house) => self.extra_houses[house].clone()
+    }
+  }
+
+  // Houses other than the columns, rows and sub-grids, solved generically: the jigsaw regions, the diagonals of the
+  //   variant and the extra houses
+  fn get_other_houses(&self) -> Vec<House> {
+    let mut houses: Vec<House> = (0..self.regions.len()).map(House::Region).collect();
+
+    if self.variant == Variant::Diagonal {
+      houses.push(House::Diagonal(0));
+      houses.push(House::Diagonal(1));
+    }
+
+    houses.extend((0..self.extra_houses.len()).map(House::Extra));
+    houses
+  }
+
+  // Other houses the cell at position is in
+  fn get_position_houses(&self, position: &Position) -> Vec<House> {
+    self.get_other_houses().into_iter()
+      .filter(|house| self.get_house_positions(house).contains(position))
+      .collect()
+  }
+
+  // Cells a move apart from the cell at position the rule applies to
+  fn get_rule_neighbours(&self, position: &Position, rule: Rule) -> Vec<Position> {
+    let moves: &[(isize, isize)] = match rule {
+      Rule::AntiKnight => &[(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)],
+      Rule::AntiKing => &[(1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)],
+      Rule::NonConsecutive => &ORTHOGONAL
+    };
+
+    self.get_neighbours(position, moves)
+  }
+
+  // Cells within the grid the moves apart from the cell at position
+  fn get_neighbours(&self, position: &Position, moves: &[(isize, isize)]) -> Vec<Position> {
+    let (column, row) = self.coordinates(position);
+    moves.iter()
+      .map(|&(x, y)| (column as isize + x, row as isize + y))
+      .filter(|&(x, y)| x >= 0 && y >= 0 && (x as usize) < self.max_options && (y as usize) < self.max_options)
+      .map(|(x, y)| self.position(x as usize, y as usize))
+      .collect()
+  }
+
+  // Options the rule removes from the neighbours of a cell set to option i.e. consecutive symbols are the next bits
+  fn rule_options(&self, rule: Rule, option: T) -> T {
+    match rule {
+      Rule::AntiKnight | Rule::AntiKing => option,
+      Rule::NonConsecutive => (option << 1 | option >> 1) & T::mask(self.max_options)
+    }
+  }
+
+  // Options the edge clues between the cells allow across from the options - all options if there are none
+  fn edge_options(&self, position: &Position, neighbour: &Position, options: T) -> T {
+    self.edges.iter()
+      .filter(|edge| edge.joins(position, neighbour))
+      .fold(T::mask(self.max_options), |allowed, edge| allowed & edge.clue.options(options, self.max_options))
+  }
+
+  // Options across from the option ruled out by the negative clues
+  fn negative_options(&self, position: &Position, neighbour: &Position, option: T) -> T {
+    self.negative_clues.iter()
+      .filter(|&&clue| self.negative_applies(clue, position, neighbour))
+      .fold(T::ZERO, |options, clue| options | clue.options(option, self.max_options))
+  }
+
+  // No edge of the clue's kind (a dot or XV) between the cells
+  fn negative_applies(&self, clue: EdgeClue, position: &Position, neighbour: &Position) -> bool {
+    !self.edges.iter().any(|edge| edge.joins(position, neighbour) && edge.clue.dot() == clue.dot())
+  }
+
+  // Distinct valid positions, one for each option
+  fn validate_house(&self, positions: &[Position]) -> Result<(), SolverError<T>> {
+    if positions.len() != self.max_options {
+      return Err(SolverError::InvalidHouse(format!("{} cells, expected {}", positions.len(), self.max_options)));
+    }
+
+    for (index, position) in positions.iter().enumerate() {
+      if !self.valid_position(position) {
+        return Err(SolverError::InvalidPosition(*position));
+      }
+      if positions[..index].contains(position) {
+        return Err(SolverError::InvalidHouse(format!("{} repeated", position)));
+      }
+    }
+
+    Ok(())
+  }
+
   fn eliminate(&mut self) -> bool {
     let mut valid = true;
     let mut total_unset_options = 2;
@@ -215,7 +991,9 @@ impl Grid {
             
             valid = match self.find_invalid_option(column_pos - 1, row_pos - 1, cell.column, cell.row, cell.options) {
               Some(option) => {
+                let cause = self.trace_cause(Some(Technique::Trial), Reason::Trial);
                 self.remove_option(column_pos - 1, row_pos - 1, cell.column, cell.row, option);
+                self.restore_cause(cause);
                 false
               },
               None => true
@@ -238,7 +1016,7 @@ impl Grid {
     !valid                                                          // Option removed?
   }
 
-  fn unset_cells(&self, column_pos: &mut usize, row_pos: &mut usize, total_unset_options: usize) -> Vec<CellOptions> {
+  fn unset_cells(&self, column_pos: &mut usize, row_pos: &mut usize, total_unset_options: usize) -> Vec<CellOptions<T>> {
     let mut cells = Vec::new();
     let mut set = false;
     while !set && *row_pos > 0 {
@@ -271,22 +1049,22 @@ impl Grid {
     sub_grid_row: usize,
     cell_column: usize,
     cell_row: usize,
-    options: u64
-  ) -> Option<u64> {
-    let mut copy = self.clone();
-
+    options: T
+  ) -> Option<T> {
     let mut valid = true;
     let mut remaining_options = options;
-    let mut try_option = remaining_options & !(remaining_options - 1);  // lowest set bit value
+    let mut try_option = remaining_options & !(remaining_options - T::ONE);  // lowest set bit value
 
-    while remaining_options > 0 && valid {
+    while remaining_options != T::ZERO && valid {
+      let mut copy = self.untraced_clone();                         // Try each option from the current state
+      copy.single_steps = false;
       copy.set_by_option(sub_grid_column, sub_grid_row, cell_column, cell_row, try_option, SetMethod::Calculated);
       copy.solve();
       valid = copy.is_valid();
 
       remaining_options -= try_option;                            // remove tried option
-      if valid && remaining_options > 0 {
-        try_option = remaining_options & !(remaining_options - 1);
+      if valid && remaining_options != T::ZERO {
+        try_option = remaining_options & !(remaining_options - T::ONE);
       }
     }
 
@@ -303,10 +1081,47 @@ impl Grid {
     sub_grid_row: usize,
     cell_column: usize,
     cell_row: usize,
-    option: u64
+    option: T
   ) {
+    let snapshot = self.trace_snapshot();
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+
+    let mut last_options_found = if self.regions.is_empty() {
+      self.strike_out_sub_grid(sub_grid_column, sub_grid_row, cell_column, cell_row, option)
+    } else {                                                        // Jigsaw i.e. the sub-grids aren't houses
+      let (column, row) = self.coordinates(&position);
+      self.remove_options_from_houses(&position, option, &[House::Column(column), House::Row(row)])
+    };
+
+    let houses = self.get_position_houses(&position);
+    last_options_found.append(&mut self.remove_options_from_houses(&position, option, &houses));
+    last_options_found.append(&mut self.remove_options_by_rules(&position, option));
+    last_options_found.append(&mut self.remove_options_by_edges(&position, option));
+
+    if let Some(snapshot) = snapshot {
+      let removed = self.removed_since(&snapshot);
+      self.record(TraceAction::StrikeOut, position, option, removed);
+    }
+
+    self.strike_out_last_options(&last_options_found);
+  }
+
+  // Option removed from the other cells of the sub-grid, column and row, and from the rest of any column or row of
+  //   another sub-grid the option is then limited to - returns the last options found
+  fn strike_out_sub_grid(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option: T
+  ) -> Vec<BitOption<T>> {
     let mut struck_out_cells = self.sub_grids[sub_grid_row][sub_grid_column]
       .strike_out_cell(cell_column, cell_row, option);
+    if self.single_steps {                                          // Box/line left for box_line_step
+      struck_out_cells.removed_options_from_column.clear();
+      struck_out_cells.removed_options_from_row.clear();
+    }
 
     let mut index = struck_out_cells.removed_options_from_column.len(); // Distinct
     while index > 0 {
@@ -336,39 +1151,234 @@ impl Grid {
       );
     }
 
-    struck_out_cells.last_options_found.append(
-      &mut self.remove_options_from_column(
-        sub_grid_column,
-        sub_grid_row,
-        cell_column,
-        option
-      )
-    );
+    struck_out_cells.last_options_found.append(
+      &mut self.remove_options_from_column(
+        sub_grid_column,
+        sub_grid_row,
+        cell_column,
+        option
+      )
+    );
+
+    struck_out_cells.last_options_found.append(
+      &mut self.remove_options_from_row(
+        sub_grid_column,
+        sub_grid_row,
+        cell_row,
+        option
+      )
+    );
+
+    struck_out_cells.last_options_found
+  }
+
+  pub fn fix_by_position(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option_column: usize,
+    option_row: usize
+  ) {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    let cell_options = self.get_cell(&position).options;
+    self.sub_grids[sub_grid_row][sub_grid_column].set_by_position(
+      cell_column,
+      cell_row,
+      option_column,
+      option_row,
+      SetMethod::Loaded
+    );
+
+    let option = T::bit(self.max_columns * option_row + option_column);
+    let cause = self.trace_set_method(SetMethod::Loaded);
+    self.record_set(position, option, cell_options);
+    self.strike_out(sub_grid_column, sub_grid_row, cell_column, cell_row, option);
+    self.restore_cause(cause);
+  }
+
+  // Apply a player's move, recording the cells it changed so it can be undone - nothing is recorded if nothing changed
+  pub fn play(&mut self, action: UserAction<T>) -> Result<(), SolverError<T>> {
+    let positions = self.move_positions(&action);
+    let before = self.states(&positions);
+
+    match action {
+      UserAction::SetValue { position, symbol } => self.try_set_by_symbol(
+        position.sub_grid_column,
+        position.sub_grid_row,
+        position.cell_column,
+        position.cell_row,
+        symbol,
+        SetMethod::User
+      )?,
+      UserAction::RemoveCandidate { position, option } => {
+        self.try_remove_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          option
+        )?;
+      }
+      UserAction::TogglePencilMark { position, option } => self.toggle_pencil_mark(
+        position.sub_grid_column,
+        position.sub_grid_row,
+        position.cell_column,
+        position.cell_row,
+        option
+      )?,
+      UserAction::Unfix(position) => {
+        if !self.valid_position(&position) {
+          return Err(SolverError::InvalidPosition(position));
+        }
+        self.unfix(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row);
+      }
+    }
+
+    let changes: Vec<CellChange<T>> = before.iter().zip(self.states(&positions).iter())
+      .filter(|(before, after)| before.1 != after.1)
+      .map(|(&(position, before), &(_, after))| CellChange { position, before, after })
+      .collect();
+
+    if !changes.is_empty() {
+      self.history.push(Move { action, changes });
+    }
+
+    Ok(())
+  }
+
+  // Take back the last move played - false if there is nothing to undo
+  pub fn undo(&mut self) -> bool {
+    if !self.history.can_undo() {
+      return false;
+    }
+
+    self.history.current -= 1;
+    let changes = self.history.moves[self.history.current].changes.clone();
+    for change in changes.iter() {
+      self.set_cell_state(&change.position, &change.before);
+    }
+
+    true
+  }
+
+  // Play the last move undone again - false if there is nothing to redo
+  pub fn redo(&mut self) -> bool {
+    if !self.history.can_redo() {
+      return false;
+    }
+
+    let changes = self.history.moves[self.history.current].changes.clone();
+    for change in changes.iter() {
+      self.set_cell_state(&change.position, &change.after);
+    }
+    self.history.current += 1;
+
+    true
+  }
+
+  // Undo or redo until the number of moves applied is current - false if there aren't that many moves
+  pub fn jump(&mut self, current: usize) -> bool {
+    if current > self.history.len() {
+      return false;
+    }
+
+    while self.history.current() > current {
+      self.undo();
+    }
+    while self.history.current() < current {
+      self.redo();
+    }
+
+    true
+  }
+
+  pub fn history(&self) -> &HistoryOf<T> {
+    &self.history
+  }
+
+  // History of a saved game, with the grid already in the state after its current move e.g. loaded by set_json - the
+  //   moves undone back to the start, then played again, must record the same changes and lead back to this state
+  pub fn load_history(&mut self, history: HistoryOf<T>) -> Result<(), SolverError<T>> {
+    for played in history.moves().iter() {
+      for change in played.changes.iter() {
+        if !self.valid_position(&change.position) {
+          return Err(SolverError::InvalidPosition(change.position));
+        }
+      }
+    }
+
+    let mut replay = self.untraced_clone();
+    let mut index = history.current();
+    while index > 0 {
+      index -= 1;
+      for change in history.moves()[index].changes.iter() {
+        if replay.get_cell(&change.position).state() != change.after {
+          return Err(SolverError::InvalidHistory(format!("Move {} doesn't match the cell at {}", index, change.position)));
+        }
+        replay.set_cell_state(&change.position, &change.before);
+      }
+    }
+
+    for (index, played) in history.moves().iter().enumerate() {
+      if index == history.current() && replay.cell_states() != self.cell_states() {
+        return Err(SolverError::InvalidHistory(format!("Moves played don't lead to the grid at move {}", index)));
+      }
+      replay.play(played.action)?;
+      if replay.history.moves().get(index) != Some(played) {
+        return Err(SolverError::InvalidHistory(format!("Move {} played again changes other cells", index)));
+      }
+    }
+    if history.current() == history.len() && replay.cell_states() != self.cell_states() {
+      return Err(SolverError::InvalidHistory(format!("Moves played don't lead to the grid at move {}", history.len())));
+    }
+
+    self.history = history;
+    Ok(())
+  }
 
-    struck_out_cells.last_options_found.append(
-      &mut self.remove_options_from_row(
-        sub_grid_column,
-        sub_grid_row,
-        cell_row,
-        option
-      )
-    );
+  // Take back a set cell: rebuild from the remaining loaded and user cells, keeping their set method, with the calculated
+  //   cells re-derived by striking out
+  pub fn unfix(&mut self, sub_grid_column: usize, sub_grid_row: usize, cell_column: usize, cell_row: usize) {
+    let unfixed = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    self.rebuild(|&position, state| position != unfixed &&
+      (state.set_method == SetMethod::Loaded || state.set_method == SetMethod::User));
+  }
 
-    index = struck_out_cells.last_options_found.len();
-    while index > 0 {
-      index -= 1;
-      let last_option = &struck_out_cells.last_options_found[index];
-      self.strike_out(
-        last_option.sub_grid_column,
-        last_option.sub_grid_row,
-        last_option.cell_column,
-        last_option.cell_row,
-        last_option.bits
-      );
+  // User entries that contradict the unique solution of the loaded givens: values set by the user that differ from the
+  //   solution, and solution options still removed by RemoveCandidate moves played
+  pub fn check_user_entries(&self) -> Result<Vec<Mistake<T>>, SolverError<T>> {
+    let mut givens = self.untraced_clone();
+    givens.rebuild(|_, state| state.set_method == SetMethod::Loaded);
+    let solutions = givens.count_solutions(2);
+    if !solutions.unique() {
+      return Err(SolverError::NotUnique(solutions.total));
+    }
+    let solution = &solutions.grids[0];
+
+    let mut mistakes = Vec::new();
+    for (position, state) in self.cell_states().iter() {
+      let solution_option = solution.get_cell(position).options;
+      if state.set_method == SetMethod::User && state.options != solution_option {
+        mistakes.push(Mistake::WrongValue { position: *position, option: state.options, solution: solution_option });
+      }
+    }
+
+    for played in self.history.moves()[..self.history.current()].iter() {
+      if let UserAction::RemoveCandidate { position, option } = played.action {
+        let mistake = Mistake::RemovedSolution { position, option };
+        if option == solution.get_cell(&position).options && !self.get_cell(&position).contains_option(option) &&
+          !mistakes.contains(&mistake) {
+          mistakes.push(mistake);
+        }
+      }
     }
+
+    Ok(mistakes)
   }
 
-  pub fn fix_by_position(
+  pub fn try_fix_by_position(
     &mut self,
     sub_grid_column: usize,
     sub_grid_row: usize,
@@ -376,17 +1386,23 @@ impl Grid {
     cell_row: usize,
     option_column: usize,
     option_row: usize
-  ) {
-    self.sub_grids[sub_grid_row][sub_grid_column].set_by_position(
-      cell_column,
-      cell_row,
-      option_column,
-      option_row,
-      SetMethod::Loaded
-    );
+  ) -> Result<(), SolverError<T>> {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    if !self.valid_position(&position) {
+      return Err(SolverError::InvalidPosition(position));
+    }
 
-    let option = 1 << (self.max_columns * option_row + option_column);
-    self.strike_out(sub_grid_column, sub_grid_row, cell_column, cell_row, option);
+    if option_column >= self.max_columns || option_row >= self.max_rows {  // Before multiplying, which could overflow
+      return Err(SolverError::InvalidOptionPosition { column: option_column, row: option_row });
+    }
+
+    let option = T::bit(self.max_columns * option_row + option_column);
+    if !self.get_cell(&position).contains_option(option) {
+      return Err(self.set_option_error(&position, option));
+    }
+
+    self.fix_by_position(sub_grid_column, sub_grid_row, cell_column, cell_row, option_column, option_row);
+    Ok(())
   }
 
   pub fn set_by_option(
@@ -395,11 +1411,16 @@ impl Grid {
     sub_grid_row: usize,
     cell_column: usize,
     cell_row: usize,
-    option: u64,
+    option: T,
     set_method: SetMethod
   ) {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    let cell_options = self.get_cell(&position).options;
     self.sub_grids[sub_grid_row][sub_grid_column].set_by_option(cell_column, cell_row, option, set_method);
+    let cause = self.trace_set_method(set_method);
+    self.record_set(position, option, cell_options);
     self.strike_out(sub_grid_column, sub_grid_row, cell_column, cell_row, option);
+    self.restore_cause(cause);
   }
 
   fn _load_option(
@@ -408,7 +1429,7 @@ impl Grid {
     sub_grid_row: usize,
     cell_column: usize,
     cell_row: usize,
-    option: u64
+    option: T
   ) {
     self.sub_grids[sub_grid_row][sub_grid_column].set_by_option(cell_column, cell_row, option, SetMethod::Calculated);
   }
@@ -422,13 +1443,45 @@ impl Grid {
     symbol: char,
     set_method: SetMethod
   ) {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    let cell_options = self.get_cell(&position).options;
     let option = self.sub_grids[sub_grid_row][sub_grid_column].set_by_symbol(
       cell_column,
       cell_row,
       symbol,
       set_method
     );
+    let cause = self.trace_set_method(set_method);
+    self.record_set(position, option, cell_options);
     self.strike_out(sub_grid_column, sub_grid_row, cell_column, cell_row, option);
+    self.restore_cause(cause);
+  }
+
+  pub fn try_set_by_symbol(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    symbol: char,
+    set_method: SetMethod
+  ) -> Result<(), SolverError<T>> {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    if !self.valid_position(&position) {
+      return Err(SolverError::InvalidPosition(position));
+    }
+
+    let option = match SYMBOLS.iter().position(|&x| x == symbol) {
+      Some(index) if index < self.max_options => T::bit(index),
+      _ => return Err(SolverError::InvalidSymbol(symbol))
+    };
+
+    if !self.get_cell(&position).contains_option(option) {
+      return Err(self.set_option_error(&position, option));
+    }
+
+    self.set_by_symbol(sub_grid_column, sub_grid_row, cell_column, cell_row, symbol, set_method);
+    Ok(())
   }
   
   pub fn set_by_index(
@@ -440,18 +1493,159 @@ impl Grid {
     index: usize,
     set_method: SetMethod
   ) {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    let cell_options = self.get_cell(&position).options;
     let option = self.sub_grids[sub_grid_row][sub_grid_column].set_by_index(
       cell_column,
       cell_row,
       index,
       set_method
     );
+    let cause = self.trace_set_method(set_method);
+    self.record_set(position, option, cell_options);
     self.strike_out(sub_grid_column, sub_grid_row, cell_column, cell_row, option);
+    self.restore_cause(cause);
   }
 
-  pub fn to_options(&mut self) -> Vec<usize> {
-    self.available_options_rows().iter()
-      .flat_map(|x| x.iter().map(|&x| x  as usize)).collect()
+  pub fn get_cell(&self, position: &Position) -> &CellOf<T> {
+    &self.sub_grids[position.sub_grid_row][position.sub_grid_column].cells[position.cell_row][position.cell_column]
+  }
+
+  fn get_cell_mut(&mut self, position: &Position) -> &mut CellOf<T> {
+    &mut self.sub_grids[position.sub_grid_row][position.sub_grid_column].cells[position.cell_row][position.cell_column]
+  }
+
+  // Position of the cell at the column and row within the whole grid (not separated by sub grids)
+  pub fn position(&self, column: usize, row: usize) -> Position {
+    Position {
+      sub_grid_column: column / self.max_rows,                      // Sub-grid's columns i.e. swopped rows
+      sub_grid_row: row / self.max_columns,
+      cell_column: column % self.max_rows,
+      cell_row: row % self.max_columns
+    }
+  }
+
+  // Column and row within the whole grid of the cell at position
+  pub fn coordinates(&self, position: &Position) -> (usize, usize) {
+    (
+      position.sub_grid_column * self.max_rows + position.cell_column,
+      position.sub_grid_row * self.max_columns + position.cell_row
+    )
+  }
+
+  // Index of the cell at position within the set options i.e. as loaded by load_set_options or returned by to_options
+  pub fn options_index(&self, position: &Position) -> usize {
+    (position.sub_grid_row * self.max_columns + position.sub_grid_column) * self.max_options +
+      position.cell_row * self.max_rows + position.cell_column
+  }
+
+  pub fn valid_position(&self, position: &Position) -> bool {
+    position.sub_grid_column < self.max_columns &&
+    position.sub_grid_row < self.max_rows &&
+    position.cell_column < self.max_rows &&                         // dimensions columns & rows swopped
+    position.cell_row < self.max_columns
+  }
+
+  // Cells sharing a column, row, sub-grid (or jigsaw region), diagonal or extra house with the cell at position
+  pub fn get_peers(&self, position: &Position) -> Vec<Position> {
+    let (column, row) = self.coordinates(position);
+    let mut peers = Vec::with_capacity(3 * self.max_options);
+
+    for index in 0..self.max_options {
+      if index != column {
+        peers.push(self.position(index, row));
+      }
+      if index != row {
+        peers.push(self.position(column, index));
+      }
+    }
+
+    if self.regions.is_empty() {
+      for cell_row in 0..self.max_columns {
+        for cell_column in 0..self.max_rows {
+          if cell_column != position.cell_column && cell_row != position.cell_row {  // Not already in the column or row
+            peers.push(Position { cell_column, cell_row, ..*position });
+          }
+        }
+      }
+    }
+
+    for house in self.get_position_houses(position).iter() {
+      for peer in self.get_house_positions(house).into_iter() {
+        if peer != *position && !peers.contains(&peer) {
+          peers.push(peer);
+        }
+      }
+    }
+
+    peers
+  }
+
+  // Set as loaded, including a cell already struck out to the option by an earlier given
+  fn load_given(&mut self, position: &Position, option: T) {
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = *position;
+    self.set_by_option(sub_grid_column, sub_grid_row, cell_column, cell_row, option, SetMethod::Loaded);
+
+    let cell = &mut self.sub_grids[sub_grid_row][sub_grid_column].cells[cell_row][cell_column];
+    if cell.options == option {
+      cell.set_method = SetMethod::Loaded;
+    }
+  }
+
+  // Option no longer available at position - either duplicates a given or was removed by a contradiction
+  fn set_option_error(&self, position: &Position, option: T) -> SolverError<T> {
+    let duplicated = self.get_peers(position).iter().any(|peer| {
+      let cell = self.get_cell(peer);
+      (cell.set_method == SetMethod::Loaded || cell.set_method == SetMethod::User) && cell.options == option
+    });
+
+    if duplicated {
+      SolverError::DuplicateGiven { position: *position, symbol: SYMBOLS[power_of_2_bit_positions(option)] }
+    } else {
+      SolverError::Contradiction(Contradiction::OptionRemoved { position: *position, option })
+    }
+  }
+
+  // Options of each cell of an edge not allowed across from any option of the other cell removed
+  fn apply_edges(&mut self) -> bool {
+    let mut removed = false;
+
+    for index in 0..self.edges.len() {
+      let edge = self.edges[index];
+      let cause = self.trace_cause(None, Reason::Edge(index));
+      for (position, other) in [(edge.first, edge.second), (edge.second, edge.first)].iter() {
+        let allowed = edge.clue.options(self.get_cell(other).options, self.max_options);
+        let before = self.get_cell(position).options;
+        if allowed & before != T::ZERO {                            // Left for the consistency check if nothing allowed
+          let mut remaining = before & !allowed;
+          while remaining != T::ZERO {
+            let option = remaining & !(remaining - T::ONE);         // lowest set bit value
+            remaining -= option;
+            self.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+          }
+          removed |= self.get_cell(position).options != before;
+        }
+      }
+      self.restore_cause(cause);
+    }
+
+    removed
+  }
+
+  fn apply_constraints(&mut self) -> bool {
+    let mut removed = false;
+    let constraints = std::mem::take(&mut self.constraints);        // Borrowed while the grid is changed
+    let combinations = std::mem::take(&mut self.constraint_combinations);
+
+    for (index, item) in constraints.iter().enumerate() {
+      let cause = self.trace_cause(None, Reason::Constraint(index));
+      removed |= constraint::apply(self, item, &combinations[index]);
+      self.restore_cause(cause);
+    }
+
+    self.constraints = constraints;
+    self.constraint_combinations = combinations;
+    removed
   }
 
   // Check for mulitipe options limited to a certain number of related cells i.e. 2 cells in a row can only contain 1 or 2 => remove from other cells in row
@@ -469,16 +1663,21 @@ impl Grid {
       limited_option_found = self.remove_if_extra_options_from_sub_grid(&limited_options);
     }
 
+    if !limited_option_found {
+      limited_options = self.find_options_limited_to_matrix(self.get_other_cells_matrix());
+      limited_option_found = self.remove_if_extra_options_from_house(&limited_options);
+    }
+
     limited_option_found
   }
 
-  fn find_options_limited_to_matrix(&self, cells: Vec<Vec<&Cell>>) -> Vec<CellOptions> {
-    let mut limited_options: Vec<CellOptions> = Vec::new();
-    let mut unset_cells: Vec<&Cell> = Vec::new();
-    let mut pick_options: Vec<&Cell> = Vec::new();
-    let mut combination_options: Vec<u64> = Vec::new();
+  fn find_options_limited_to_matrix(&self, cells: Vec<Vec<&CellOf<T>>>) -> Vec<CellOptions<T>> {
+    let mut limited_options: Vec<CellOptions<T>> = Vec::new();
+    let mut unset_cells: Vec<&CellOf<T>> = Vec::new();
+    let mut pick_options: Vec<&CellOf<T>> = Vec::new();
+    let mut combination_options: Vec<T> = Vec::new();
 
-    for cell_index in 0..self.max_options {
+    for cell_index in 0..cells.len() {
       unset_cells.clear();
 
       // IEnumerable<Cell> unset_cells = cells[index].Where(x => !x.IsSet);  // Get cells that are still to be set
@@ -522,7 +1721,10 @@ impl Grid {
           }
         }
 
-        let combinations = self.combinations.select(&pick_options, pick);
+        let mut combinations = Vec::new();
+        if self.combinations.count(pick_options.len(), pick) <= MAX_SUBSET_COMBINATIONS {  // Too many in larger houses
+          combinations = self.combinations.select(&pick_options, pick);
+        }
         index = combinations.len();
         while !found && index > 0 {
           index -= 1;
@@ -549,15 +1751,19 @@ impl Grid {
     limited_options
   }
 
-  fn find_options_limited_to_sub_grids(&self) -> Vec<CellOptions> {
+  fn find_options_limited_to_sub_grids(&self) -> Vec<CellOptions<T>> {
+    if !self.regions.is_empty() {                                   // Jigsaw i.e. the sub-grids aren't houses
+      return Vec::new();
+    }
+
     let mut limited_options = Vec::new();
-    let mut pick_options: Vec<&Cell> = Vec::new();
     let mut combination_options = Vec::new();
     
     for row in 0..self.max_rows {
       for column in 0..self.max_columns {
         let unset_cells = self.sub_grids[row][column].get_unset_cells();
         let total_unset_cells = unset_cells.len();
+        let mut pick_options: Vec<&CellOf<T>> = Vec::new();         // Borrowed from this sub-grid's unset cells
 
         // int max_remaining_options = unset_cells.Where(x => x.TotalOptionsRemaining < total_unset_cells).Max(x => (int?) x.TotalOptionsRemaining) ?? 0;    // Max < total_unset_cells
         let mut max_remaining_options = 0;
@@ -584,15 +1790,18 @@ impl Grid {
           while index > 0 {
             index -= 1;
             if unset_cells[index].total_options_remaining <= pick {
-              // let t: &Cell = &unset_cells[index];
-              // pick_options.push(t);      // ToDo:
+              pick_options.push(&unset_cells[index]);
             }
           }
 
-          let combinations = self.combinations.select(&pick_options, pick);
+          let mut combinations = Vec::new();
+          if self.combinations.count(pick_options.len(), pick) <= MAX_SUBSET_COMBINATIONS {  // Too many in larger sub-grids
+            combinations = self.combinations.select(&pick_options, pick);
+          }
           index = combinations.len();
           while !found && index > 0 {
             index -= 1;
+            combination_options.clear();                            // Only the options of this combination
             // int remove_options = BitwiseOR(enumerator.Current.Select(x => x.Options));
             let mut combinations_index = combinations[index].len();
             while combinations_index > 0 {
@@ -619,13 +1828,15 @@ impl Grid {
     limited_options
   }
 
-  fn remove_if_extra_options_from_column(&mut self, limited_options: &Vec<CellOptions>) -> bool {
+  fn remove_if_extra_options_from_column(&mut self, limited_options: &Vec<CellOptions<T>>) -> bool {
     let mut last_options = Vec::new();
 
     let mut index = limited_options.len();
     while index > 0 {
       index -= 1;
       let limited_option = &limited_options[index];
+      let snapshot = self.trace_snapshot();
+      let cause = self.trace_limited_options(House::Column(limited_option.column), limited_option.options);
       for row in 0..self.max_rows {
         last_options.append(&mut self.sub_grids[row][
             (limited_option.column / self.max_rows) >> 0
@@ -635,31 +1846,24 @@ impl Grid {
           )
         );
       }
+      self.record_removed(snapshot);
+      self.restore_cause(cause);
     }
 
-    index = last_options.len();
-    while index > 0 {
-      index -= 1;
-      let last_option = &last_options[index];
-      self.strike_out(
-        last_option.sub_grid_column,
-        last_option.sub_grid_row,
-        last_option.cell_column,
-        last_option.cell_row,
-        last_option.bits
-      );
-    }
+    self.strike_out_last_options(&last_options);
 
     last_options.len() > 0
   }
 
-  fn remove_if_extra_options_from_row(&mut self, limited_options: &Vec<CellOptions>) -> bool {
+  fn remove_if_extra_options_from_row(&mut self, limited_options: &Vec<CellOptions<T>>) -> bool {
     let mut last_options = Vec::new();
 
     let mut index = limited_options.len();
     while index > 0 {
       index -= 1;
       let limited_option = &limited_options[index];
+      let snapshot = self.trace_snapshot();
+      let cause = self.trace_limited_options(House::Row(limited_option.row), limited_option.options);
       for column in 0..self.max_columns {
         last_options.append(
           &mut self.sub_grids[(limited_option.row / self.max_columns) >> 0][column]
@@ -669,51 +1873,141 @@ impl Grid {
             )
         )
       }
+      self.record_removed(snapshot);
+      self.restore_cause(cause);
     }
 
-    index = last_options.len();
-    while index > 0 {
-      index -= 1;
-      let last_option = &last_options[index];
-      self.strike_out(
-        last_option.sub_grid_column,
-        last_option.sub_grid_row,
-        last_option.cell_column,
-        last_option.cell_row,
-        last_option.bits
-      );
-    }
+    self.strike_out_last_options(&last_options);
 
     last_options.len() > 0
   }
 
-  fn remove_if_extra_options_from_sub_grid(&mut self, limited_options: &Vec<CellOptions>) -> bool {
+  fn remove_if_extra_options_from_sub_grid(&mut self, limited_options: &Vec<CellOptions<T>>) -> bool {
     let mut last_options = Vec::new();
 
     let mut index = limited_options.len();
     while index > 0 {
       index -= 1;
       let limited_option = &limited_options[index];
+      let snapshot = self.trace_snapshot();
+      let cause = self.trace_limited_options(
+        House::SubGrid(limited_option.column, limited_option.row),
+        limited_option.options
+      );
       last_options.append(
         &mut self.sub_grids[limited_option.row][limited_option.column]
           .remove_if_extra_options(limited_option.options)
-      )
+      );
+      self.record_removed(snapshot);
+      self.restore_cause(cause);
     }
 
-    index = last_options.len();
+    self.strike_out_last_options(&last_options);
+
+    last_options.len() > 0
+  }
+
+  fn remove_if_extra_options_from_house(&mut self, limited_options: &[CellOptions<T>]) -> bool {
+    let mut last_options = Vec::new();
+
+    let mut index = limited_options.len();
     while index > 0 {
       index -= 1;
-      let last_option = &last_options[index];
-      self.strike_out(
-        last_option.sub_grid_column,
-        last_option.sub_grid_row,
-        last_option.cell_column,
-        last_option.cell_row,
-        last_option.bits
-      );
+      let limited_option = &limited_options[index];
+      let house = self.get_other_houses()[limited_option.column];
+      let snapshot = self.trace_snapshot();
+      let cause = self.trace_limited_options(house, limited_option.options);
+      for position in self.get_house_positions(&house).iter() {
+        let cell = self.get_cell_mut(position);
+        if cell.remove_options(limited_option.options) {
+          last_options.push(BitOption {
+            sub_grid_column: position.sub_grid_column,
+            sub_grid_row: position.sub_grid_row,
+            cell_column: position.cell_column,
+            cell_row: position.cell_row,
+            bits: cell.options
+          });
+        }
+      }
+      self.record_removed(snapshot);
+      self.restore_cause(cause);
     }
 
-    last_options.len() > 0
+    self.strike_out_last_options(&last_options);
+
+    !last_options.is_empty()
+  }
+
+  // Option of the solved cell at position removed from the other cells of the houses
+  fn remove_options_from_houses(&mut self, position: &Position, option: T, houses: &[House]) -> Vec<BitOption<T>> {
+    let mut last_options = Vec::new();
+
+    for house in houses.iter() {
+      for other in self.get_house_positions(house).iter().filter(|&other| other != position) {
+        let cell = self.get_cell_mut(other);
+        if cell.remove_option(option) {
+          last_options.push(BitOption {
+            sub_grid_column: other.sub_grid_column,
+            sub_grid_row: other.sub_grid_row,
+            cell_column: other.cell_column,
+            cell_row: other.cell_row,
+            bits: cell.options
+          });
+        }
+      }
+    }
+
+    last_options
+  }
+
+  // Options ruled out of the neighbours of the solved cell at position by each rule
+  fn remove_options_by_rules(&mut self, position: &Position, option: T) -> Vec<BitOption<T>> {
+    let mut last_options = Vec::new();
+
+    for rule in self.rules.clone().into_iter() {
+      let options = self.rule_options(rule, option);
+      for neighbour in self.get_rule_neighbours(position, rule).iter() {
+        let cell = self.get_cell_mut(neighbour);
+        if cell.remove_options(options) {
+          last_options.push(BitOption {
+            sub_grid_column: neighbour.sub_grid_column,
+            sub_grid_row: neighbour.sub_grid_row,
+            cell_column: neighbour.cell_column,
+            cell_row: neighbour.cell_row,
+            bits: cell.options
+          });
+        }
+      }
+    }
+
+    last_options
+  }
+
+  // Options not allowed across the edges from the solved cell at position removed from its neighbours
+  fn remove_options_by_edges(&mut self, position: &Position, option: T) -> Vec<BitOption<T>> {
+    let mut last_options = Vec::new();
+    if self.edges.is_empty() && self.negative_clues.is_empty() {
+      return last_options;
+    }
+
+    let all_options = T::mask(self.max_options);
+    for neighbour in self.get_neighbours(position, &ORTHOGONAL).iter() {
+      let remove = (all_options & !self.edge_options(position, neighbour, option)) |
+        self.negative_options(position, neighbour, option);
+
+      let cell = self.get_cell_mut(neighbour);
+      if remove != T::ZERO && cell.remove_options(remove) {
+        last_options.push(BitOption {
+          sub_grid_column: neighbour.sub_grid_column,
+          sub_grid_row: neighbour.sub_grid_row,
+          cell_column: neighbour.cell_column,
+          cell_row: neighbour.cell_row,
+          bits: cell.options
+        });
+      }
+    }
+
+    last_options
   }
 
   fn remove_options_from_column(
@@ -721,8 +2015,8 @@ impl Grid {
     sub_grid_column: usize,
     sub_grid_row: usize,
     cell_column: usize,
-    options: u64
-  ) -> Vec<BitOption> {
+    options: T
+  ) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     // Ignore sub_grid_row
@@ -749,122 +2043,256 @@ impl Grid {
     last_options
   }
 
-  fn remove_options_from_row(
-    &mut self,
-    sub_grid_column: usize,
-    sub_grid_row: usize,
-    cell_row: usize,
-    options: u64
-  ) -> Vec<BitOption> {
-    let mut last_options = Vec::new();
-
-    // Ignore sub_grid_column
-    let mut column = self.max_columns - 1;
-    while column > sub_grid_column {
-      last_options.append(&mut self.sub_grids[sub_grid_row][column].remove_options_from_row(cell_row, options));
-      column -= 1;
+  fn remove_options_from_row(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_row: usize,
+    options: T
+  ) -> Vec<BitOption<T>> {
+    let mut last_options = Vec::new();
+
+    // Ignore sub_grid_column
+    let mut column = self.max_columns - 1;
+    while column > sub_grid_column {
+      last_options.append(&mut self.sub_grids[sub_grid_row][column].remove_options_from_row(cell_row, options));
+      column -= 1;
+    }
+
+    while column > 0 {
+      column -= 1;
+      last_options.append(&mut self.sub_grids[sub_grid_row][column].remove_options_from_row(cell_row, options));
+    }
+
+    last_options
+  }
+  
+  fn remove_only_options(&mut self) -> bool {
+    self.remove_only_column_options() ||
+    self.remove_only_row_options() ||
+    self.remove_only_sub_grid_options() ||
+    self.remove_only_house_options()
+  }
+
+  fn remove_only_column_options(&mut self) -> bool {
+    match self.find_only_column_option() {
+      Some((position, bit)) => {
+        let cause = self.trace_cause(Some(Technique::HiddenSingle), Reason::OnlyOption(House::Column(self.coordinates(&position).0)));
+        self.set_by_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          bit,
+          SetMethod::Calculated
+        );
+        self.restore_cause(cause);
+        true
+      },
+      None => false
+    }
+  }
+
+  fn remove_only_row_options(&mut self) -> bool {
+    match self.find_only_row_option() {
+      Some((position, bit)) => {
+        let cause = self.trace_cause(Some(Technique::HiddenSingle), Reason::OnlyOption(House::Row(self.coordinates(&position).1)));
+        self.set_by_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          bit,
+          SetMethod::Calculated
+        );
+        self.restore_cause(cause);
+        true
+      },
+      None => false
+    }
+  }
+
+  fn remove_only_sub_grid_options(&mut self) -> bool {
+    match self.find_only_sub_grid_option() {
+      Some((position, bit)) => {
+        let cause = self.trace_cause(Some(Technique::HiddenSingle), Reason::OnlyOption(House::SubGrid(position.sub_grid_column, position.sub_grid_row)));
+        self.set_by_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          bit,
+          SetMethod::Calculated
+        );
+        self.restore_cause(cause);
+        true
+      },
+      None => false
+    }
+  }
+
+  fn remove_only_house_options(&mut self) -> bool {
+    match self.find_only_house_option() {
+      Some((house, position, bit)) => {
+        let cause = self.trace_cause(Some(Technique::HiddenSingle), Reason::OnlyOption(house));
+        self.set_by_option(
+          position.sub_grid_column,
+          position.sub_grid_row,
+          position.cell_column,
+          position.cell_row,
+          bit,
+          SetMethod::Calculated
+        );
+        self.restore_cause(cause);
+        true
+      },
+      None => false
+    }
+  }
+
+  // Option removed from the rest of a house it's limited to where it meets another house, as when striking out the
+  //   sub-grids' columns and rows
+  fn check_house_intersections(&mut self) -> bool {
+    match self.find_house_intersection() {
+      Some((house, option, positions)) => {
+        let cause = self.trace_cause(Some(Technique::BoxLineReduction), Reason::BoxLine(house));
+        for position in positions.iter() {
+          self.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+        }
+        self.restore_cause(cause);
+        true
+      },
+      None => false
+    }
+  }
+
+  // House with an option limited to where it meets another house, at least one of them an other house e.g. a jigsaw
+  //   region and a row - with the unsolved cells of the other house to remove the option from
+  fn find_house_intersection(&self) -> Option<(House, T, Vec<Position>)> {
+    if self.get_other_houses().is_empty() {                         // Sub-grids and lines checked when striking out
+      return None;
     }
 
-    while column > 0 {
-      column -= 1;
-      last_options.append(&mut self.sub_grids[sub_grid_row][column].remove_options_from_row(cell_row, options));
+    let houses = self.get_houses();
+    let other_house = |house: &House| !matches!(house, House::Column(_) | House::Row(_) | House::SubGrid(..));
+
+    for (house, positions) in houses.iter() {
+      for (meets, meets_positions) in houses.iter() {
+        if house == meets || !(other_house(house) || other_house(meets)) ||
+          !positions.iter().any(|position| meets_positions.contains(position)) {
+          continue;
+        }
+
+        for bit in 0..self.max_options {
+          let option = T::bit(bit);
+          let containing: Vec<&Position> = positions.iter()
+            .filter(|&position| self.get_cell(position).contains_option(option))
+            .collect();
+
+          if !containing.is_empty() && containing.iter().all(|&position| meets_positions.contains(position)) {
+            let remove: Vec<Position> = meets_positions.iter()
+              .filter(|&position| !positions.contains(position))
+              .filter(|&position| !self.get_cell(position).solved() && self.get_cell(position).contains_option(option))
+              .copied()
+              .collect();
+
+            if !remove.is_empty() {
+              return Some((*house, option, remove));
+            }
+          }
+        }
+      }
     }
 
-    last_options
-  }
-  
-  fn remove_only_options(&mut self) -> bool {
-    self.remove_only_column_options() ||
-    self.remove_only_row_options() ||
-    self.remove_only_sub_grid_options()
+    None
   }
 
-  fn remove_only_column_options(&mut self) -> bool {
-    let mut only_option_found = false;
-
+  // Cell containing an option found in no other cell of its column
+  fn find_only_column_option(&self) -> Option<(Position, T)> {
     let matrix = self.get_transposed_available_options_matrix();
 
     // Check for only options in each column
     let mut column = self.max_options;
-    while !only_option_found && column > 0 {
+    while column > 0 {
       column -= 1;
       let (found, bit) = only_option(&matrix[column]);
 
       if found {
-        only_option_found = true;
         let matrix_row = containing_bit_index(&matrix[column], bit);  // Row within grid where only option found
-        self.set_by_option(
-          (column / self.max_rows) >> 0,
-          (matrix_row / self.max_columns) >> 0,
-          column % self.max_rows,
-          matrix_row % self.max_columns,
-          bit,
-          SetMethod::Calculated
-        );
+        return Some((self.position(column, matrix_row), bit));
       }
     }
 
-    only_option_found
+    None
   }
 
-  fn remove_only_row_options(&mut self) -> bool {
-    let mut only_option_found = false;
-
+  // Cell containing an option found in no other cell of its row
+  fn find_only_row_option(&self) -> Option<(Position, T)> {
     let matrix = self.get_available_options_matrix();
 
     // Check for only options in each row
     let mut row = self.max_options;
-    while !only_option_found && row > 0 {
+    while row > 0 {
       row -= 1;
       let (found, bit) = only_option(&matrix[row]);
 
       if found {
-        only_option_found = true;
         let matrix_column = containing_bit_index(&matrix[row], bit);  // Column within grid where only option found
-        self.set_by_option(
-          (matrix_column / self.max_rows) >> 0,
-          (row / self.max_columns) >> 0,
-          matrix_column % self.max_rows,
-          row % self.max_columns,
-          bit,
-          SetMethod::Calculated
-        );
+        return Some((self.position(matrix_column, row), bit));
       }
     }
 
-    only_option_found
+    None
   }
 
-  fn remove_only_sub_grid_options(&mut self) -> bool {
-    let mut only_option_found = false;
+  // Cell containing an option found in no other cell of its sub grid
+  fn find_only_sub_grid_option(&self) -> Option<(Position, T)> {
+    if !self.regions.is_empty() {                                   // Jigsaw i.e. the sub-grids aren't houses
+      return None;
+    }
 
     // Check for only options in each sub grid
     let mut row = self.max_rows;
-    while !only_option_found && row > 0 {
+    while row > 0 {
       row -= 1;
 
       let mut column = self.max_columns;
-      while !only_option_found && column > 0 {
+      while column > 0 {
         column -= 1;
         let values = self.sub_grids[row][column].get_available_options();
         let (found, bit) = only_option(&values);
 
         if found {
-          only_option_found = true;
           let array_index = containing_bit_index(&values, bit);     // Index within array where only option found
-          self.set_by_option(
-            column,
-            row,
-            array_index % self.max_rows,
-            (array_index / self.max_rows) >> 0,
-            bit,
-            SetMethod::Calculated
-          );
+          return Some((
+            Position {
+              sub_grid_column: column,
+              sub_grid_row: row,
+              cell_column: array_index % self.max_rows,
+              cell_row: (array_index / self.max_rows) >> 0
+            },
+            bit
+          ));
         }
       }
     }
 
-    only_option_found
+    None
+  }
+
+  // Cell containing an option found in no other cell of one of the other houses
+  fn find_only_house_option(&self) -> Option<(House, Position, T)> {
+    for house in self.get_other_houses().into_iter() {
+      let positions = self.get_house_positions(&house);
+      let values: Vec<T> = positions.iter().map(|position| self.get_cell(position).options).collect();
+      let (found, bit) = only_option(&values);
+
+      if found {
+        return Some((house, positions[containing_bit_index(&values, bit)], bit));
+      }
+    }
+
+    None
   }
 
   // Check options removed from other columns (n - 1) columns must have the options removed i.e. option must exist in only 1 column
@@ -873,8 +2301,8 @@ impl Grid {
     sub_grid_column: usize,
     sub_grid_row: usize,
     cell_column: usize,
-    option: u64
-  ) -> Vec<BitOption> {
+    option: T
+  ) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     let mut total_existing_columns = 0;
@@ -932,8 +2360,8 @@ impl Grid {
     sub_grid_column: usize,
     sub_grid_row: usize,
     cell_row: usize,
-    option: u64
-  ) -> Vec<BitOption> {
+    option: T
+  ) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     let mut total_existing_columns = 0;
@@ -985,11 +2413,398 @@ impl Grid {
     last_options
   }
 
+  ////////////////////////////////////////////////////////////////////////////////////////////
+  // Steps i.e. a single deduction compared to the grid after applying it
+  ////////////////////////////////////////////////////////////////////////////////////////////
+
+  fn step(&self, technique: Technique, house: Option<House>, cells: Vec<Position>, after: &Self) -> Step<T> {
+    let mut set = Vec::new();
+    let mut removed = Vec::new();
+
+    for row in 0..self.max_options {
+      for column in 0..self.max_options {
+        let position = self.position(column, row);
+        let before_cell = self.get_cell(&position);
+        let after_cell = after.get_cell(&position);
+
+        if after_cell.solved() && !before_cell.solved() {
+          set.push(OptionChange { position, options: after_cell.options });
+        } else if after_cell.options != before_cell.options {
+          removed.push(OptionChange { position, options: before_cell.options & !after_cell.options });
+        }
+      }
+    }
+
+    Step { technique, house, cells, set, removed }
+  }
+
+  // Solved cell with its option still in another cell of its column, row or sub-grid
+  fn naked_single_step(&self) -> Option<Step<T>> {
+    for row in 0..self.max_options {
+      for column in 0..self.max_options {
+        let position = self.position(column, row);
+        let option = self.get_cell(&position).options;
+
+        if self.get_cell(&position).solved() &&
+          self.get_peers(&position).iter().any(|peer| self.get_cell(peer).contains_option(option)) {
+          let mut after = self.untraced_clone();
+          after.strike_out(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+
+          let mut step = self.step(Technique::NakedSingle, None, vec![position], &after);
+          step.set.insert(0, OptionChange { position, options: option });
+          return Some(step);
+        }
+      }
+    }
+
+    None
+  }
+
+  fn hidden_single_step(&self) -> Option<Step<T>> {
+    let only_option = self.find_only_column_option()
+      .map(|(position, bit)| (House::Column(self.coordinates(&position).0), position, bit))
+      .or_else(|| self.find_only_row_option()
+        .map(|(position, bit)| (House::Row(self.coordinates(&position).1), position, bit)))
+      .or_else(|| self.find_only_sub_grid_option()
+        .map(|(position, bit)| (House::SubGrid(position.sub_grid_column, position.sub_grid_row), position, bit)))
+      .or_else(|| self.find_only_house_option());
+
+    only_option.map(|(house, position, bit)| {
+      let mut after = self.untraced_clone();
+      after.set_by_option(
+        position.sub_grid_column,
+        position.sub_grid_row,
+        position.cell_column,
+        position.cell_row,
+        bit,
+        SetMethod::Calculated
+      );
+
+      self.step(Technique::HiddenSingle, Some(house), vec![position], &after)
+    })
+  }
+
+  fn subset_step(&self) -> Option<Step<T>> {
+    for limited_option in self.find_options_limited_to_matrix(self.get_transposed_cells_matrix()).iter() {
+      let mut after = self.untraced_clone();
+      after.remove_if_extra_options_from_column(&vec![CellOptions { ..*limited_option }]);
+
+      let step = self.subset(House::Column(limited_option.column), limited_option.options, &after);
+      if !step.removed.is_empty() || !step.set.is_empty() {
+        return Some(step);
+      }
+    }
+
+    for limited_option in self.find_options_limited_to_matrix(self.get_cells_matrix()).iter() {
+      let mut after = self.untraced_clone();
+      after.remove_if_extra_options_from_row(&vec![CellOptions { ..*limited_option }]);
+
+      let step = self.subset(House::Row(limited_option.row), limited_option.options, &after);
+      if !step.removed.is_empty() || !step.set.is_empty() {
+        return Some(step);
+      }
+    }
+
+    for limited_option in self.find_options_limited_to_sub_grids().iter() {
+      let mut after = self.untraced_clone();
+      after.remove_if_extra_options_from_sub_grid(&vec![CellOptions { ..*limited_option }]);
+
+      let step = self.subset(House::SubGrid(limited_option.column, limited_option.row), limited_option.options, &after);
+      if !step.removed.is_empty() || !step.set.is_empty() {
+        return Some(step);
+      }
+    }
+
+    for limited_option in self.find_options_limited_to_matrix(self.get_other_cells_matrix()).iter() {
+      let mut after = self.untraced_clone();
+      after.remove_if_extra_options_from_house(&[CellOptions { ..*limited_option }]);
+
+      let step = self.subset(self.get_other_houses()[limited_option.column], limited_option.options, &after);
+      if !step.removed.is_empty() || !step.set.is_empty() {
+        return Some(step);
+      }
+    }
+
+    None
+  }
+
+  fn subset(&self, house: House, options: T, after: &Self) -> Step<T> {
+    let (technique, cells) = self.subset_cells(&house, options);
+    self.step(technique, Some(house), cells, after)
+  }
+
+  // Cells limited to the options form a naked subset, the other unsolved cells a hidden subset - use the smaller one
+  fn subset_cells(&self, house: &House, options: T) -> (Technique, Vec<Position>) {
+    let (subset, others): (Vec<Position>, Vec<Position>) = self.get_house_positions(house).into_iter()
+      .filter(|position| !self.get_cell(position).solved())
+      .partition(|position| self.get_cell(position).options & !options == T::ZERO);
+
+    if others.len() < subset.len() {
+      (Technique::HiddenSubset, others)
+    } else {
+      (Technique::NakedSubset, subset)
+    }
+  }
+
+  // Check each sub-grid column/row without an option, as when striking out, for the option limited to one column/row
+  //   of the sub-grid (removed from the rest of the column/row) or to one sub-grid of the column/row (removed from the
+  //   rest of the sub-grid)
+  fn box_line_step(&self) -> Option<Step<T>> {
+    if !self.regions.is_empty() {                                   // Jigsaw i.e. the sub-grids aren't houses
+      return None;
+    }
+
+    for sub_grid_row in 0..self.max_rows {
+      for sub_grid_column in 0..self.max_columns {
+        for bit in 0..self.max_options {
+          let option = T::bit(bit);
+
+          for cell_column in 0..self.max_rows {                     // Use SubGrid's number of columns i.e. swopped rows
+            if !self.sub_grids[sub_grid_row][sub_grid_column].option_exists_in_column(cell_column, option) {
+              let mut after = self.untraced_clone();
+              let last_options = after.remove_option_from_other_columns(sub_grid_column, sub_grid_row, cell_column, option);
+              after.strike_out_last_options(&last_options);
+
+              let existing_columns: Vec<usize> = (0..self.max_rows)
+                .filter(|&column| after.sub_grids[sub_grid_row][sub_grid_column].option_exists_in_column(column, option))
+                .collect();
+              let column = if existing_columns.len() == 1 { existing_columns[0] } else { cell_column };
+
+              let step = self.box_line(House::Column(sub_grid_column * self.max_rows + column), option, &after);
+              if !step.removed.is_empty() || !step.set.is_empty() {
+                return Some(step);
+              }
+            }
+          }
+
+          for cell_row in 0..self.max_columns {                     // Use SubGrid's number of rows i.e. swopped columns
+            if !self.sub_grids[sub_grid_row][sub_grid_column].option_exists_in_row(cell_row, option) {
+              let mut after = self.untraced_clone();
+              let last_options = after.remove_option_from_other_rows(sub_grid_column, sub_grid_row, cell_row, option);
+              after.strike_out_last_options(&last_options);
+
+              let existing_rows: Vec<usize> = (0..self.max_columns)
+                .filter(|&row| after.sub_grids[sub_grid_row][sub_grid_column].option_exists_in_row(row, option))
+                .collect();
+              let row = if existing_rows.len() == 1 { existing_rows[0] } else { cell_row };
+
+              let step = self.box_line(House::Row(sub_grid_row * self.max_columns + row), option, &after);
+              if !step.removed.is_empty() || !step.set.is_empty() {
+                return Some(step);
+              }
+            }
+          }
+        }
+      }
+    }
+
+    None
+  }
+
+  // Option of a house limited to where it meets another house, as in check_house_intersections
+  fn house_intersection_step(&self) -> Option<Step<T>> {
+    self.find_house_intersection().map(|(house, option, positions)| {
+      let mut after = self.untraced_clone();
+      for position in positions.iter() {
+        after.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+      }
+
+      self.box_line(house, option, &after)
+    })
+  }
+
+  // Cells of the column/row left with the option are all within one sub-grid
+  fn box_line(&self, house: House, option: T, after: &Self) -> Step<T> {
+    let cells = self.get_house_positions(&house).into_iter()
+      .filter(|position| after.get_cell(position).contains_option(option))
+      .collect();
+
+    self.step(Technique::BoxLineReduction, Some(house), cells, after)
+  }
+
+  // Option of the cells with the fewest options resulting in an invalid grid, as in eliminate
+  fn trial_step(&self) -> Option<Step<T>> {
+    for total_options in 2..=self.max_options {
+      for row in 0..self.max_options {
+        for column in 0..self.max_options {
+          let position = self.position(column, row);
+          let cell = self.get_cell(&position);
+
+          if cell.total_options_remaining == total_options {
+            let invalid_option = self.find_invalid_option(
+              position.sub_grid_column,
+              position.sub_grid_row,
+              position.cell_column,
+              position.cell_row,
+              cell.options
+            );
+
+            if let Some(option) = invalid_option {
+              let mut after = self.untraced_clone();
+              after.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+              return Some(self.step(Technique::Trial, None, vec![position], &after));
+            }
+          }
+        }
+      }
+    }
+
+    None
+  }
+
+  fn strike_out_last_options(&mut self, last_options: &[BitOption<T>]) {
+    if self.single_steps {                                          // Left for naked_single_step
+      return;
+    }
+
+    let cause = match &self.trace {
+      Some(trace) => self.trace_cause(trace.cause.technique, Reason::LastOption),
+      None => None
+    };
+
+    let mut index = last_options.len();
+    while index > 0 {
+      index -= 1;
+      let last_option = &last_options[index];
+      self.strike_out(
+        last_option.sub_grid_column,
+        last_option.sub_grid_row,
+        last_option.cell_column,
+        last_option.cell_row,
+        last_option.bits
+      );
+    }
+
+    self.restore_cause(cause);
+  }
+
+  fn untraced_clone(&self) -> Self {
+    Self { trace: None, history: HistoryOf::new(), ..self.clone() }
+  }
+
+  // Copy with only the givens set and struck out, the rest left for next_step one deduction at a time e.g. a cell's
+  //   last option is a naked single of its own instead of struck out along with the option that removed it
+  pub fn single_step_clone(&self) -> Self {
+    let mut copy = self.untraced_clone();
+    copy.reset();
+    copy.single_steps = true;
+
+    for row in 0..self.max_options {
+      for column in 0..self.max_options {
+        let position = self.position(column, row);
+        let cell = self.get_cell(&position);
+
+        if cell.set_method == SetMethod::Loaded || cell.set_method == SetMethod::User {
+          copy.set_by_option(
+            position.sub_grid_column,
+            position.sub_grid_row,
+            position.cell_column,
+            position.cell_row,
+            cell.options,
+            cell.set_method
+          );
+        }
+      }
+    }
+
+    copy
+  }
+
+  ////////////////////////////////////////////////////////////////////////////////////////////
+  // Trace i.e. changes recorded while solving
+  ////////////////////////////////////////////////////////////////////////////////////////////
+
+  // Technique and reason recorded with the following changes - returns the previous ones to restore
+  fn trace_cause(&mut self, technique: Option<Technique>, reason: Reason) -> Option<Cause> {
+    match &mut self.trace {
+      Some(trace) => Some(std::mem::replace(&mut trace.cause, Cause { technique, reason })),
+      None => None
+    }
+  }
+
+  fn restore_cause(&mut self, cause: Option<Cause>) {
+    if let (Some(trace), Some(cause)) = (&mut self.trace, cause) {
+      trace.cause = cause;
+    }
+  }
+
+  fn trace_limited_options(&mut self, house: House, options: T) -> Option<Cause> {
+    match self.trace {
+      Some(_) => {
+        let (technique, _) = self.subset_cells(&house, options);
+        self.trace_cause(Some(technique), Reason::LimitedOptions(house))
+      },
+      None => None
+    }
+  }
+
+  // Options of every cell, by row, to compare against once changed
+  fn trace_snapshot(&self) -> Option<Vec<T>> {
+    self.trace.as_ref().map(|_| {
+      let mut options = Vec::with_capacity(self.max_options * self.max_options);
+      for row in 0..self.max_options {
+        for column in 0..self.max_options {
+          options.push(self.get_cell(&self.position(column, row)).options);
+        }
+      }
+
+      options
+    })
+  }
+
+  fn removed_since(&self, snapshot: &[T]) -> Vec<OptionChange<T>> {
+    let mut removed = Vec::new();
+    for row in 0..self.max_options {
+      for column in 0..self.max_options {
+        let position = self.position(column, row);
+        let options = snapshot[row * self.max_options + column] & !self.get_cell(&position).options;
+        if options != T::ZERO {
+          removed.push(OptionChange { position, options });
+        }
+      }
+    }
+
+    removed
+  }
+
+  fn record(&mut self, action: TraceAction, position: Position, options: T, removed: Vec<OptionChange<T>>) {
+    if let Some(trace) = &mut self.trace {
+      let Cause { technique, reason } = trace.cause;
+      trace.entries.push(TraceEntry { action, position, options, technique, reason, removed });
+    }
+  }
+
+  fn record_set(&mut self, position: Position, option: T, cell_options: T) {
+    let removed = match cell_options & !option {
+      options if options == T::ZERO => Vec::new(),
+      options => vec![OptionChange { position, options }]
+    };
+    self.record(TraceAction::SetByOption, position, option, removed);
+  }
+
+  // Givens and the caller's changes start a new chain of changes
+  fn trace_set_method(&mut self, set_method: SetMethod) -> Option<Cause> {
+    match set_method {
+      SetMethod::Loaded => self.trace_cause(None, Reason::Given),
+      SetMethod::User => self.trace_cause(None, Reason::User),
+      _ => None
+    }
+  }
+
+  // Record the options removed since the snapshot, one entry per cell
+  fn record_removed(&mut self, snapshot: Option<Vec<T>>) {
+    if let Some(snapshot) = snapshot {
+      for change in self.removed_since(&snapshot) {
+        self.record(TraceAction::RemoveOption, change.position, change.options, vec![change]);
+      }
+    }
+  }
+
   ////////////////////////////////////////////////////////////////////////////////////////////
   // Convert sub grids to coluns * rows matrix
   ////////////////////////////////////////////////////////////////////////////////////////////
 
-  fn get_available_options_matrix(&self) -> Vec<Vec<u64>> {
+  fn get_available_options_matrix(&self) -> Vec<Vec<T>> {
     // Get state of current grid - returned as an n*m matrix (not separated by sub grids)
     let mut matrix = Vec::with_capacity(self.max_options);
     for _ in 0..self.max_options {
@@ -1009,7 +2824,7 @@ impl Grid {
     matrix
   }
 
-  fn get_transposed_available_options_matrix(&self) -> Vec<Vec<u64>> {
+  fn get_transposed_available_options_matrix(&self) -> Vec<Vec<T>> {
     // Get state of current grid - returned as a transposed n*m matrix (not separated by sub grids)
     let mut matrix = Vec::with_capacity(self.max_options);
     for _ in 0..self.max_options {
@@ -1032,7 +2847,110 @@ impl Grid {
     matrix
   }
 
-  fn get_cells_matrix(&self) -> Vec<Vec<&Cell>> {
+  // Reset then set the cells kept with their set method, striking out from them so the other cells are re-derived - the
+  //   user's marks and history are kept
+  fn rebuild<F>(&mut self, keep: F) where F: Fn(&Position, &CellState<T>) -> bool {
+    let states = self.cell_states();
+    let highlights: Vec<T> = states.iter().map(|(position, _)| self.get_cell(position).highlights).collect();
+    let fixed_cells: Vec<(Position, CellState<T>)> = states.iter().copied()
+      .filter(|(position, state)| keep(position, state))
+      .collect();
+
+    let history = std::mem::take(&mut self.history);                // Kept, as the rebuild can be played and undone
+    self.reset();
+    self.history = history;
+
+    for (position, state) in fixed_cells.iter() {                   // Set first so striking out can't calculate them
+      self.set_cell_state(position, state);
+    }
+
+    for (position, state) in fixed_cells.iter() {
+      self.strike_out(
+        position.sub_grid_column,
+        position.sub_grid_row,
+        position.cell_column,
+        position.cell_row,
+        state.options
+      );
+    }
+
+    for ((position, state), &highlights) in states.iter().zip(highlights.iter()) {  // User's marks kept
+      let cell = &mut self.sub_grids[position.sub_grid_row][position.sub_grid_column]
+        .cells[position.cell_row][position.cell_column];
+      cell.pencil_marks = state.pencil_marks;
+      cell.highlights = highlights;
+    }
+  }
+
+  // Valid position and a single option of the grid
+  fn check_option(&self, position: &Position, option: T) -> Result<(), SolverError<T>> {
+    if !self.valid_position(position) {
+      return Err(SolverError::InvalidPosition(*position));
+    }
+
+    let all_options = T::mask(self.max_options);
+    if option == T::ZERO || option & ((option - T::ONE) | !all_options) != T::ZERO { // Not a single base of 2 number within the options
+      return Err(SolverError::InvalidOption { index: self.options_index(position), option });
+    }
+
+    Ok(())
+  }
+
+  // Cells the action can change: only the one cell for a pencil mark or one of several options removed, otherwise every
+  //   cell as striking out can carry on into any other cell - none for a position the action is then rejected for
+  fn move_positions(&self, action: &UserAction<T>) -> Vec<Position> {
+    let cell_only = match action {
+      UserAction::TogglePencilMark { position, .. } => Some(*position),
+      UserAction::RemoveCandidate { position, option } if self.valid_position(position) &&
+        (self.get_cell(position).options & !*option).count_ones() > 1 => Some(*position),
+      _ => None
+    };
+
+    match cell_only {
+      Some(position) if self.valid_position(&position) => vec![position],
+      Some(_) => Vec::new(),
+      None => self.set_options_positions()
+    }
+  }
+
+  fn states(&self, positions: &[Position]) -> Vec<(Position, CellState<T>)> {
+    positions.iter().map(|&position| (position, self.get_cell(&position).state())).collect()
+  }
+
+  // State of every cell in the same order as the set options
+  fn cell_states(&self) -> Vec<(Position, CellState<T>)> {
+    self.states(&self.set_options_positions())
+  }
+
+  fn set_options_positions(&self) -> Vec<Position> {
+    let mut positions = Vec::with_capacity(self.max_options * self.max_options);
+
+    for sub_grid_row in 0..self.max_rows {
+      for sub_grid_column in 0..self.max_columns {
+        for cell_row in 0..self.max_columns {                       // dimensions columns & rows swopped
+          for cell_column in 0..self.max_rows {
+            positions.push(Position { sub_grid_column, sub_grid_row, cell_column, cell_row });
+          }
+        }
+      }
+    }
+
+    positions
+  }
+
+  fn set_cell_state(&mut self, position: &Position, state: &CellState<T>) {
+    self.sub_grids[position.sub_grid_row][position.sub_grid_column].cells[position.cell_row][position.cell_column]
+      .set_state(state);
+  }
+
+  // Cells of each of the other houses
+  fn get_other_cells_matrix(&self) -> Vec<Vec<&CellOf<T>>> {
+    self.get_other_houses().iter()
+      .map(|house| self.get_house_positions(house).iter().map(|position| self.get_cell(position)).collect())
+      .collect()
+  }
+
+  fn get_cells_matrix(&self) -> Vec<Vec<&CellOf<T>>> {
     // Get cells in current grid - returned as an n*m matrix (not separated by sub grids)
     let mut matrix = Vec::with_capacity(self.max_options);
     for _ in 0..self.max_options {
@@ -1043,11 +2961,8 @@ impl Grid {
       for sub_grid_column in 0..self.max_columns {
         let sub_matrix = self.sub_grids[sub_grid_row][sub_grid_column].get_cells_matrix();
 
-        for cell_column in 0..self.max_rows {
-          for cell_row in 0..self.max_columns {
-            let matrix_column = sub_grid_column * self.max_rows + cell_column;
-            matrix[matrix_column].push(sub_matrix[cell_row][cell_column]);
-          }
+        for cell_row in 0..self.max_columns {
+          matrix[sub_grid_row * self.max_columns + cell_row].extend(&sub_matrix[cell_row]);
         }
       }
     }
@@ -1055,7 +2970,7 @@ impl Grid {
     matrix
   }
 
-  fn get_transposed_cells_matrix(&self) -> Vec<Vec<&Cell>> {
+  fn get_transposed_cells_matrix(&self) -> Vec<Vec<&CellOf<T>>> {
     // Get state of current grid - returned as a transposed n*m matrix (not separated by sub grids)
     let mut matrix = Vec::with_capacity(self.max_options);
     for _ in 0..self.max_options {
@@ -1079,23 +2994,23 @@ impl Grid {
   }
 }
 
-fn distinct_set_options(cells: &Vec<&Cell>) -> Vec<u64> {
-  cells.iter().fold(HashSet::new(), |mut distinct_cells, cell| {
-    if cell.set_method != SetMethod::Unset {
-      distinct_cells.insert(cell.options);
-    }
-    distinct_cells
-  }).into_iter().collect()
-}
+// Options as numbers e.g. for JavaScript - a number for each cell, or more (lowest bits first) for grids with more
+//   symbols than the bits of a number
+impl<T: OptionBits> GridOf<T> {
+  pub fn to_options(&mut self) -> Vec<usize> {
+    let words = option_words(self.max_options);
+    self.available_options_rows().iter()
+      .flat_map(|x| x.iter().flat_map(move |&x| x.to_words(words))).collect()
+  }
 
-fn unset_options(cells: &Vec<&Cell>) -> Vec<u64> {
-  // cells.Where(x => !x.IsSet).Select(x => x.Options)
-  let mut options = Vec::with_capacity(cells.len());
-  for index in 0..cells.len() {
-    if cells[index].set_method == SetMethod::Unset {
-      options.push(cells[index].options);
+  // Options of every cell from numbers as returned by to_options
+  pub fn from_options(&self, numbers: &[usize]) -> Result<Vec<T>, SolverError<T>> {
+    let words = option_words(self.max_options);
+    let expected = self.max_options * self.max_options * words;
+    if numbers.len() != expected {
+      return Err(SolverError::InvalidInputLength { expected, actual: numbers.len() });
     }
-  }
 
-  options
+    Ok(numbers.chunks(words).map(T::from_words).collect())
+  }
 }
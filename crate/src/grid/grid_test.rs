@@ -1,4 +1,6 @@
 #[cfg(test)]
+use crate::grid::grid::Grid;
+#[cfg(test)]
 use crate::sub_grid::sub_grid::SubGrid;
 
 #[cfg(test)]
@@ -14,6 +16,42 @@ fn init_sub_grids(columns: usize, rows: usize) -> Vec<Vec<SubGrid>> {
   sub_grids
 }
 
+// Convert a row by row puzzle e.g. "1..2" into the set options of each sub-grid, as passed to load_set_options
+#[cfg(test)]
+fn set_options(columns: usize, rows: usize, puzzle: &str) -> Vec<u64> {
+  let max_options = columns * rows;
+  let symbols: Vec<char> = puzzle.chars().collect();
+  let mut options = Vec::with_capacity(symbols.len());
+
+  for sub_grid_row in 0..rows {
+    for sub_grid_column in 0..columns {
+      for cell_row in 0..columns {                                  // Sub-grid's columns and rows swopped
+        for cell_column in 0..rows {
+          let index = (sub_grid_row * columns + cell_row) * max_options + sub_grid_column * rows + cell_column;
+          options.push(match symbols[index].to_digit(10) {
+            Some(digit) if digit > 0 => 1 << (digit - 1),
+            _ => 0
+          });
+        }
+      }
+    }
+  }
+
+  options
+}
+
+// 3 x 2 puzzle used by the modules below, row by row
+#[cfg(test)]
+const PUZZLE: [&str; 6] = ["....1.", "2.....", ".6...4", "...4.2", "..5...", "5.1..3"];
+
+#[cfg(test)]
+fn puzzle() -> Grid {
+  let mut grid = Grid::new(3, 2);
+  grid.load_set_options(&set_options(3, 2, &PUZZLE.concat()));
+
+  grid
+}
+
 #[cfg(test)]
 mod grid {
   use crate::cell::SetMethod;
@@ -293,6 +331,44 @@ mod grid_2x2 {
     grid.set_by_option(1, 0, 1, 0, 2, SetMethod::User);             // top right set to 2
     assert!(grid.solve());
   }
+
+  #[test]
+  fn it_checks_the_rows_of_the_grid() {
+    let mut grid = Grid::new(2, 2);
+
+    let input = vec![                                               //    | 2 ||   |
+      0, 2, 0, 8,                                                   // ---|---||---|---
+      0, 0, 0, 2,                                                   //    | 4 ||   | 2
+      0, 0, 0, 0,                                                   // ===|===||===|===
+      0, 4, 0, 0                                                    //    |   ||   | 3
+    ];                                                              // ---|---||---|---
+                                                                    //    |   ||   |
+    grid.load_set_options(&input);
+    assert_eq!(grid.try_solve(), Ok(false));                        // Valid, not a contradiction found in the columns
+  }
+
+  #[test]
+  fn it_only_removes_options_invalid_from_the_current_state() {
+    let mut grid = Grid::new(2, 2);
+
+    let input = vec![                                               //    | 2 || 3 |
+      0, 2, 0, 0,                                                   // ---|---||---|---
+      4, 0, 0, 0,                                                   //    |   ||   |
+      2, 1, 0, 0,                                                   // ===|===||===|===
+      8, 0, 0, 0                                                    //  2 | 1 || 4 |
+    ];                                                              // ---|---||---|---
+                                                                    //    |   ||   |
+    let solution = vec![                                            //  1 | 2 || 3 | 4
+      1, 2, 4, 8,                                                   // ---|---||---|---
+      4, 8, 1, 2,                                                   //  3 | 4 || 1 | 2
+      2, 1, 8, 4,                                                   // ===|===||===|===
+      8, 4, 2, 1                                                    //  2 | 1 || 4 | 3
+    ];                                                              // ---|---||---|---
+                                                                    //  4 | 3 || 2 | 1
+    grid.load_set_options(&input);
+    assert_eq!(grid.try_solve(), Ok(false));                        // More than one solution
+    assert!(grid.to_options().iter().zip(solution.iter()).all(|(&options, &option)| options & option > 0));
+  }
 }
 
 #[cfg(test)]
@@ -341,3 +417,1437 @@ mod grid_3x2 {
     assert!(false);
   }
 }
+
+#[cfg(test)]
+mod grid_errors {
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+  use crate::grid::{grid::{Grid, LargeGrid, SmallGrid}, Position, House, Contradiction};
+
+  #[test]
+  fn it_rejects_invalid_dimensions() {
+    assert_eq!(Grid::try_new(0, 3).err(), Some(SolverError::InvalidDimensions { columns: 0, rows: 3 }));
+    assert_eq!(Grid::try_new(9, 8).err(), Some(SolverError::InvalidDimensions { columns: 9, rows: 8 }));  // More than 64 symbols
+    assert_eq!(SmallGrid::try_new(5, 4).err(), Some(SolverError::InvalidDimensions { columns: 5, rows: 4 }));
+    assert_eq!(LargeGrid::try_new(11, 10).err(), Some(SolverError::InvalidDimensions { columns: 11, rows: 10 }));
+    assert!(Grid::try_new(2, 3).is_ok());
+    assert!(Grid::try_new(8, 8).is_ok());
+    assert!(LargeGrid::try_new(10, 10).is_ok());
+  }
+
+  #[test]
+  fn it_rejects_the_wrong_input_length() {
+    let mut grid = Grid::new(2, 2);
+
+    let result = grid.try_load_set_options(&vec![0; 15]);
+    assert_eq!(result, Err(SolverError::InvalidInputLength { expected: 16, actual: 15 }));
+  }
+
+  #[test]
+  fn it_rejects_invalid_option_bits() {
+    let mut grid = Grid::new(2, 2);
+
+    let mut input = vec![0; 16];
+    input[5] = 3;                                                   // Options 1 and 2 i.e. not a single option
+    assert_eq!(grid.try_load_set_options(&input), Err(SolverError::InvalidOption { index: 5, option: 3 }));
+
+    input[5] = 16;                                                  // Beyond the 4 options of a 2 x 2 grid
+    assert_eq!(grid.try_load_set_options(&input), Err(SolverError::InvalidOption { index: 5, option: 16 }));
+  }
+
+  #[test]
+  fn it_rejects_duplicate_givens() {
+    let mut grid = Grid::new(2, 2);
+
+    let input = vec![                                               //  1 |   ||   | 1
+      1, 0, 0, 0,                                                   // ---|---||---|---
+      0, 1, 0, 0,                                                   //    |   ||   |
+      0, 0, 0, 0,
+      0, 0, 0, 0
+    ];
+
+    let expected = SolverError::DuplicateGiven {
+      position: Position { sub_grid_column: 1, sub_grid_row: 0, cell_column: 1, cell_row: 0 },
+      symbol: '1'
+    };
+    assert_eq!(grid.try_load_set_options(&input), Err(expected));
+  }
+
+  #[test]
+  fn it_finds_a_contradiction_while_loading() {
+    let mut grid = Grid::new(2, 2);
+
+    let input = vec![                                               //  1 | 2 ||   |         Top right cells limited to 3 or 4
+      1, 2, 0, 0,                                                   // ---|---||---|---
+      0, 0, 0, 0,                                                   //    |   ||   |
+      0, 0, 0, 0,                                                   // ===|===||===|===
+      4, 0, 8, 0                                                    //    |   || 3 |         3 and 4 leave no option for the cell
+    ];                                                              // ---|---||---|---        above them
+                                                                    //    |   || 4 |
+
+    let position = Position { sub_grid_column: 1, sub_grid_row: 1, cell_column: 0, cell_row: 1 };
+    let expected = SolverError::Contradiction(Contradiction::OptionRemoved { position, option: 8 });
+    assert_eq!(grid.try_load_set_options(&input), Err(expected));
+  }
+
+  #[test]
+  fn it_finds_a_contradiction_while_solving() {
+    let mut grid = Grid::new(2, 2);
+
+    let input = vec![                                               //    | 2 ||   |
+      0, 2, 0, 4,                                                   // ---|---||---|---
+      0, 0, 0, 8,                                                   //    | 3 ||   | 4
+      0, 1, 0, 0,                                                   // ===|===||===|===
+      4, 0, 0, 0                                                    //    | 1 || 3 |        4 forced below the 4 on the right
+    ];                                                              // ---|---||---|---
+                                                                    //    |   ||   |
+
+    assert!(grid.try_load_set_options(&input).is_ok());
+
+    let expected = SolverError::Contradiction(Contradiction::DuplicateOption {
+      house: House::Column(2),
+      option: 1,
+      positions: vec![
+        Position { sub_grid_column: 1, sub_grid_row: 0, cell_column: 0, cell_row: 0 },
+        Position { sub_grid_column: 1, sub_grid_row: 1, cell_column: 0, cell_row: 1 }
+      ]
+    });
+    assert_eq!(grid.try_solve(), Err(expected));
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_symbol() {
+    let mut grid = Grid::new(2, 2);
+
+    assert_eq!(grid.try_set_by_symbol(0, 0, 0, 0, '5', SetMethod::User), Err(SolverError::InvalidSymbol('5')));
+    assert_eq!(grid.try_set_by_symbol(0, 0, 0, 0, '?', SetMethod::User), Err(SolverError::InvalidSymbol('?')));
+    assert!(grid.try_set_by_symbol(0, 0, 0, 0, '4', SetMethod::User).is_ok());
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_position() {
+    let mut grid = Grid::new(3, 2);                                 // Sub-grids of 2 columns and 3 rows
+
+    let position = Position { sub_grid_column: 0, sub_grid_row: 0, cell_column: 2, cell_row: 0 };
+    assert_eq!(grid.try_set_by_symbol(0, 0, 2, 0, '1', SetMethod::User), Err(SolverError::InvalidPosition(position)));
+    assert!(grid.try_set_by_symbol(0, 0, 1, 2, '1', SetMethod::User).is_ok());
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_fix() {
+    let mut grid = Grid::new(3, 2);                                 // Cells of 3 columns and 2 rows of options
+
+    let position = Position { sub_grid_column: 0, sub_grid_row: 2, cell_column: 0, cell_row: 0 };
+    assert_eq!(grid.try_fix_by_position(0, 2, 0, 0, 0, 0), Err(SolverError::InvalidPosition(position)));
+    assert_eq!(grid.try_fix_by_position(0, 0, 0, 0, 0, 2), Err(SolverError::InvalidOptionPosition { column: 0, row: 2 }));
+    assert_eq!(grid.try_fix_by_position(0, 0, 0, 0, 3, 0), Err(SolverError::InvalidOptionPosition { column: 3, row: 0 }));
+    assert_eq!(
+      grid.try_fix_by_position(0, 0, 0, 0, 0, usize::MAX),          // Without overflowing
+      Err(SolverError::InvalidOptionPosition { column: 0, row: usize::MAX })
+    );
+    assert!(grid.try_fix_by_position(0, 0, 0, 0, 2, 1).is_ok());    // 6
+
+    let position = Position { sub_grid_column: 1, sub_grid_row: 0, cell_column: 0, cell_row: 0 };
+    assert_eq!(
+      grid.try_fix_by_position(1, 0, 0, 0, 2, 1),
+      Err(SolverError::DuplicateGiven { position, symbol: '6' })
+    );
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_option_to_remove() {
+    let mut grid = Grid::new(2, 2);
+
+    let position = Position { sub_grid_column: 2, sub_grid_row: 0, cell_column: 0, cell_row: 0 };
+    assert_eq!(grid.try_remove_option(2, 0, 0, 0, 1), Err(SolverError::InvalidPosition(position)));
+    assert_eq!(grid.try_remove_option(0, 0, 1, 0, 3), Err(SolverError::InvalidOption { index: 1, option: 3 }));
+    assert_eq!(grid.try_remove_option(0, 0, 1, 0, 16), Err(SolverError::InvalidOption { index: 1, option: 16 }));
+    assert_eq!(grid.try_remove_option(0, 0, 1, 0, 4), Ok(false));
+    assert_eq!(grid.try_remove_option(0, 0, 1, 0, 2), Ok(false));
+    assert_eq!(grid.try_remove_option(0, 0, 1, 0, 1), Ok(true));    // Only 8 left
+  }
+
+  #[test]
+  fn it_solves_a_valid_puzzle() {
+    let mut grid = Grid::new(2, 2);
+
+    let input = vec![                                               //  1 |   ||   |
+      1, 0, 0, 2,                                                   // ---|---||---|---
+      0, 0, 0, 0,                                                   //    | 2 ||   |
+      0, 0, 0, 0,                                                   // ===|===||===|===
+      4, 0, 0, 8                                                    //    |   || 3 |
+    ];                                                              // ---|---||---|---
+                                                                    //    |   ||   | 4
+    assert!(grid.try_load_set_options(&input).is_ok());
+    assert_eq!(grid.try_solve(), Ok(false));                        // Valid, but more than one solution
+  }
+}
+
+#[cfg(test)]
+mod grid_consistency {
+  use crate::grid::{grid::Grid, Position, House, Contradiction};
+
+  #[test]
+  fn it_is_consistent() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&vec![
+      1, 0, 0, 2,
+      0, 0, 0, 0,
+      0, 0, 0, 0,
+      4, 0, 0, 8
+    ]);
+
+    assert_eq!(grid.check_consistency(), None);
+    assert!(grid.is_valid());
+  }
+
+  #[test]
+  fn it_finds_duplicate_options_in_a_row() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&vec![                                    //  1 |   ||   | 1
+      1, 0, 0, 0,                                                   // ---|---||---|---
+      0, 1, 0, 0,                                                   //    |   ||   |
+      0, 0, 0, 0,
+      0, 0, 0, 0
+    ]);
+
+    let expected = Contradiction::DuplicateOption {
+      house: House::Row(0),
+      option: 1,
+      positions: vec![
+        Position { sub_grid_column: 0, sub_grid_row: 0, cell_column: 0, cell_row: 0 },
+        Position { sub_grid_column: 1, sub_grid_row: 0, cell_column: 1, cell_row: 0 }
+      ]
+    };
+    assert_eq!(grid.check_consistency(), Some(expected));
+    assert_eq!(grid.is_valid(), false);
+  }
+
+  #[test]
+  fn it_finds_an_option_missing_from_a_row() {
+    let mut grid = Grid::new(2, 2);
+    grid.remove_option(0, 0, 0, 0, 1);                              // Remove option 1 from the top row
+    grid.remove_option(0, 0, 1, 0, 1);
+    grid.remove_option(1, 0, 0, 0, 1);
+    grid.remove_option(1, 0, 1, 0, 1);
+
+    let expected = Contradiction::MissingOption {
+      house: House::Row(0),
+      option: 1,
+      positions: (0..4).map(|column| grid.position(column, 0)).collect()
+    };
+    assert_eq!(grid.check_consistency(), Some(expected));
+  }
+
+  #[test]
+  fn it_returns_the_positions_to_highlight() {
+    let grid = Grid::new(3, 2);
+
+    let position = grid.position(4, 5);                             // Sub-grids of 2 columns and 3 rows
+    assert_eq!(position, Position { sub_grid_column: 2, sub_grid_row: 1, cell_column: 0, cell_row: 2 });
+    assert_eq!(grid.coordinates(&position), (4, 5));
+
+    let contradiction: Contradiction = Contradiction::NoOptions(position);
+    assert_eq!(contradiction.positions(), vec![position]);
+  }
+}
+
+#[cfg(test)]
+mod grid_exhaustive {
+  use crate::grid::grid::Grid;
+
+  #[test]
+  fn it_solves_a_hard_puzzle() {
+    let mut grid = Grid::new(3, 3);
+    grid.load_set_options(&super::set_options(3, 3, concat!(
+      "4.....8.5",
+      ".3.......",
+      "...7.....",
+      ".2.....6.",
+      "....8.4..",
+      "....1....",
+      "...6.3.7.",
+      "5..2.....",
+      "1.4......"
+    )));
+
+    assert!(grid.solve_exhaustive());
+    assert!(grid.solved());
+    assert!(grid.is_valid());
+    assert_eq!(grid.to_options(), super::set_options(3, 3, concat!(
+      "417369825",
+      "632158947",
+      "958724316",
+      "825437169",
+      "791586432",
+      "346912758",
+      "289643571",
+      "573291684",
+      "164875293"
+    )).iter().map(|&x| x as usize).collect::<Vec<usize>>());
+  }
+
+  #[test]
+  fn it_fills_an_empty_4x4_grid() {
+    let mut grid = Grid::new(4, 4);                                 // 16 x 16 cells without any givens
+
+    assert!(grid.solve_exhaustive());                               // Subsets only looked for before the first guess
+    assert!(grid.solved());
+    assert!(grid.is_valid());
+  }
+
+  #[test]
+  fn it_proves_there_is_no_solution() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&super::set_options(2, 2, concat!(
+      "..34",                                                       // Top left 3 cells limited to 1 or 2
+      "....",
+      "3...",
+      "4..."
+    )));
+
+    assert!(grid.is_valid());                                       // No contradiction until searched
+    assert_eq!(grid.solve_exhaustive(), false);
+  }
+}
+
+#[cfg(test)]
+mod grid_solutions {
+  use crate::grid::grid::Grid;
+
+  #[test]
+  fn it_counts_a_unique_solution() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&super::set_options(2, 2, concat!(
+      "1...",
+      "..3.",
+      ".4..",
+      "...2"
+    )));
+
+    let solutions = grid.count_solutions(2);
+    assert_eq!(solutions.total, 1);
+    assert!(solutions.unique());
+    assert_eq!(solutions.grids.len(), 1);
+    assert!(solutions.grids[0].solved());
+  }
+
+  #[test]
+  fn it_returns_the_first_two_distinct_solutions() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&super::set_options(2, 2, concat!(
+      "1...",
+      ".2..",
+      "..3.",
+      "...4"
+    )));
+
+    let mut solutions = grid.count_solutions(2);
+    assert_eq!(solutions.total, 2);
+    assert_eq!(solutions.unique(), false);
+    assert!(solutions.grids[0].solved() && solutions.grids[0].is_valid());
+    assert!(solutions.grids[1].solved() && solutions.grids[1].is_valid());
+
+    let second = solutions.grids[1].to_options();
+    assert_ne!(solutions.grids[0].to_options(), second);
+  }
+
+  #[test]
+  fn it_stops_counting_at_the_limit() {
+    let grid = Grid::new(2, 2);                                     // 288 solutions
+
+    let solutions = grid.count_solutions(10);
+    assert_eq!(solutions.total, 10);
+    assert_eq!(solutions.grids.len(), 2);                           // Only the first two kept
+  }
+
+  #[test]
+  fn it_counts_no_solutions() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&super::set_options(2, 2, concat!(
+      "..34",
+      "....",
+      "3...",
+      "4..."
+    )));
+
+    assert_eq!(grid.count_solutions(2).total, 0);
+  }
+
+  #[test]
+  fn it_gives_up_counting_after_the_max_guesses() {
+    let grid = Grid::new(2, 2);
+
+    assert!(grid.try_count_solutions(2, 1).is_none());              // More than one guess to a solution
+    assert_eq!(grid.try_count_solutions(2, 16).map(|solutions| solutions.total), Some(2));
+  }
+}
+
+#[cfg(test)]
+mod grid_steps {
+  use crate::grid::grid::Grid;
+  use crate::grid::{House, Technique};
+
+  fn hard_puzzle() -> Grid {
+    let mut grid = Grid::new(3, 3);
+    grid.load_set_options(&super::set_options(3, 3, concat!(
+      "4.....8.5",
+      ".3.......",
+      "...7.....",
+      ".2.....6.",
+      "....8.4..",
+      "....1....",
+      "...6.3.7.",
+      "5..2.....",
+      "1.4......"
+    )));
+
+    grid
+  }
+
+  // Apply each step until no more steps, returning the techniques used
+  fn solve_by_steps(grid: &mut Grid) -> Vec<Technique> {
+    let mut techniques = Vec::new();
+    while let Some(step) = grid.next_step() {
+      assert!(!step.set.is_empty() || !step.removed.is_empty());
+      grid.apply_step(&step);
+      techniques.push(step.technique);
+    }
+
+    techniques
+  }
+
+  #[test]
+  fn it_returns_the_next_step_without_applying_it() {
+    let mut grid = hard_puzzle();
+    let options = grid.to_options();
+
+    let step = grid.next_step().unwrap();
+    assert_eq!(step.technique, Technique::HiddenSingle);
+    assert_eq!(step.house, Some(House::Column(1)));
+    assert_eq!(step.set[0].position, step.cells[0]);
+    assert_eq!(grid.to_options(), options);                         // Not applied
+
+    grid.apply_step(&step);
+    assert_eq!(grid.get_cell(&step.cells[0]).options, step.set[0].options);
+  }
+
+  #[test]
+  fn it_solves_a_puzzle_step_by_step() {
+    let mut grid = hard_puzzle();
+    let mut solution = grid.clone();
+    solution.solve_exhaustive();
+
+    let techniques = solve_by_steps(&mut grid);
+    assert!(techniques.contains(&Technique::NakedSubset));
+    assert!(techniques.contains(&Technique::HiddenSubset));
+    assert!(!techniques.contains(&Technique::Trial));
+    assert!(grid.solved());
+    assert_eq!(grid.to_options(), solution.to_options());
+  }
+
+  #[test]
+  fn it_finds_a_subset_within_a_sub_grid() {
+    let mut grid = hard_puzzle();
+    let mut sub_grid_subsets = Vec::new();
+
+    while let Some(step) = grid.next_step() {
+      if let (Technique::HiddenSubset, Some(House::SubGrid(column, row))) = (step.technique, step.house) {
+        sub_grid_subsets.push((column, row));
+      }
+      grid.apply_step(&step);
+    }
+
+    assert!(sub_grid_subsets.contains(&(0, 2)));                    // Before any box/line reduction is needed
+    assert!(grid.solved());
+  }
+
+  #[test]
+  fn it_reduces_an_option_to_where_a_sub_grid_meets_a_line() {
+    let mut grid = Grid::new(3, 3);
+    grid.load_set_options(&super::set_options(3, 3, concat!(
+      ".7.86....",
+      ".9...5..3",
+      "........6",
+      "..67.381.",
+      "8...1..9.",
+      "..92.....",
+      "........4",
+      ".2.3.....",
+      ".3...7.69"
+    )));
+
+    let mut box_line_steps = Vec::new();
+    while let Some(step) = grid.next_step() {
+      if step.technique == Technique::BoxLineReduction {
+        box_line_steps.push(step.clone());
+      }
+      grid.apply_step(&step);
+    }
+
+    assert_eq!(box_line_steps.len(), 1);
+    let step = &box_line_steps[0];
+    assert_eq!(step.house, Some(House::Column(6)));                 // Only in the bottom right sub-grid's first column
+    assert!(step.cells.iter().all(|cell| (cell.sub_grid_column, cell.sub_grid_row, cell.cell_column) == (2, 2, 0)));
+    assert!(!step.removed.is_empty());
+    assert!(grid.solved());
+  }
+
+  #[test]
+  fn it_eliminates_an_option_by_trial() {
+    let mut grid = Grid::new(3, 3);
+    grid.load_set_options(&super::set_options(3, 3, concat!(
+      "8.....69.",
+      "....1....",
+      ".2.3....8",
+      "5..2....7",
+      "3..897...",
+      ".6.....3.",
+      "6.4.8.2..",
+      ".9.56....",
+      "........1"
+    )));
+
+    let techniques = solve_by_steps(&mut grid);
+    assert!(techniques.contains(&Technique::Trial));
+    assert!(grid.solved());
+    assert!(grid.is_valid());
+  }
+
+  #[test]
+  fn it_returns_no_step_once_solved() {
+    let mut grid = hard_puzzle();
+    grid.solve_exhaustive();
+
+    assert_eq!(grid.next_step(), None);
+  }
+}
+
+#[cfg(test)]
+mod grid_trace {
+  use crate::grid::grid::Grid;
+  use crate::grid::{Reason, Technique, Trace, TraceAction};
+
+  fn easy_puzzle() -> Grid {
+    let mut grid = Grid::new(3, 2);
+    grid.enable_trace();
+    grid.load_set_options(&super::set_options(3, 2, concat!(
+      "....1.",
+      "2.....",
+      ".6...4",
+      "...4.2",
+      "..5...",
+      "5.1..3"
+    )));
+
+    grid
+  }
+
+  #[test]
+  fn it_only_records_once_enabled() {
+    let mut grid = easy_puzzle();
+    grid.take_trace();
+    grid.solve();
+
+    assert!(grid.trace().is_none());
+  }
+
+  #[test]
+  fn it_records_the_givens() {
+    let grid = easy_puzzle();
+    let trace = grid.trace().unwrap();
+
+    let givens: Vec<_> = trace.iter().filter(|entry| entry.action == TraceAction::SetByOption).collect();
+    assert_eq!(givens.len(), 10);
+    assert!(trace.iter().all(|entry| entry.reason == Reason::Given || entry.reason == Reason::LastOption));
+    assert!(trace.iter().all(|entry| entry.technique == None));
+  }
+
+  #[test]
+  fn it_replays_the_options_removed() {
+    let mut grid = easy_puzzle();
+    assert!(grid.solve());
+
+    let mut options = vec![vec![0b111111; 6]; 6];
+    for entry in grid.trace().unwrap() {
+      for change in entry.removed.iter() {
+        let (column, row) = grid.coordinates(&change.position);
+        options[row][column] &= !change.options;
+      }
+    }
+
+    for row in 0..6 {
+      for column in 0..6 {
+        assert_eq!(options[row][column], grid.get_cell(&grid.position(column, row)).options);
+      }
+    }
+  }
+
+  #[test]
+  fn it_records_the_technique_and_reason() {
+    let mut grid = easy_puzzle();
+    grid.solve();
+
+    let hidden_single = grid.trace().unwrap().iter()
+      .find(|entry| entry.technique == Some(Technique::HiddenSingle))
+      .unwrap();
+    assert_eq!(hidden_single.action, TraceAction::SetByOption);
+    assert!(match hidden_single.reason {
+      Reason::OnlyOption(_) => true,
+      _ => false
+    });
+  }
+
+  #[test]
+  fn it_round_trips_through_json() {
+    let mut grid = easy_puzzle();
+    grid.solve();
+    let trace = grid.take_trace().unwrap();
+
+    let json = trace.to_json();
+    assert!(json.starts_with("[{\"action\":\"setByOption\",\"position\":{\"subGridColumn\":0,"));
+    assert_eq!(Trace::from_json(&json).unwrap(), trace);
+    assert!(Trace::from_json("{").is_err());
+  }
+}
+
+#[cfg(test)]
+mod grid_json {
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+  use crate::grid::{grid::Grid, JsonGrid, Position};
+  use super::puzzle;
+
+  #[test]
+  fn it_converts_to_json() {
+    let grid = puzzle();
+    let json = grid.to_json();
+
+    assert_eq!(json.rows.len(), 2);
+    assert_eq!(json.rows[0].columns.len(), 3);
+    assert_eq!((json.rows[1].columns[2].column, json.rows[1].columns[2].row), (2, 1));
+    assert_eq!(json.rows[0].columns[2].rows[0].columns[0].symbol, Some('1'));  // Sub-grid (2, 0) cell (0, 0)
+    assert_eq!(json.rows[0].columns[2].rows[0].columns[0].set_method, Some(SetMethod::Loaded));
+
+    let text = json.to_json();
+    assert!(text.starts_with(r#"{"rows":[{"columns":[{"column":0,"row":0,"rows":[{"columns":[{"column":0,"row":0,"rows":"#));
+    assert!(text.contains(r#"{"symbol":"1","strikeOut":true,"highlight":false,"pencilMark":false}"#));
+    assert!(text.contains(r#""symbol":"1","setMethod":"loaded""#));
+  }
+
+  #[test]
+  fn it_round_trips_through_json() {
+    let mut grid = puzzle();
+    grid.set_by_symbol(0, 0, 0, 0, '3', SetMethod::User);          // Set method kept
+    let text = grid.to_json().to_json();
+
+    let mut copy = Grid::new(3, 2);
+    copy.set_json(&JsonGrid::from_json(&text).unwrap()).unwrap();
+    assert_eq!(copy.to_options(), grid.to_options());
+    assert_eq!(copy.to_json(), grid.to_json());
+
+    let position = Position { sub_grid_column: 0, sub_grid_row: 0, cell_column: 0, cell_row: 0 };
+    assert_eq!(copy.get_cell(&position).set_method, SetMethod::User);
+  }
+
+  #[test]
+  fn it_rejects_json_of_another_size() {
+    let json = puzzle().to_json();
+    let mut grid = Grid::new(2, 2);
+
+    assert!(matches!(grid.set_json(&json), Err(SolverError::InvalidJson(_))));
+    assert!(matches!(JsonGrid::from_json("{\"rows\":1}"), Err(SolverError::InvalidJson(_))));
+  }
+
+  #[test]
+  fn it_is_unchanged_by_an_invalid_cell() {
+    let mut json = puzzle().to_json();
+    json.rows[1].columns[2].rows[2].columns[1].symbol = Some('7');
+
+    let mut grid = Grid::new(3, 2);
+    assert_eq!(grid.set_json(&json), Err(SolverError::InvalidSymbol('7')));
+    assert_eq!(grid.to_options(), Grid::new(3, 2).to_options());
+  }
+}
+
+#[cfg(test)]
+mod grid_unfix {
+  use crate::cell::SetMethod;
+  use crate::grid::grid::Grid;
+  use super::puzzle;
+
+  fn set_user_option(grid: &mut Grid) -> (usize, usize, usize, usize, u64) {
+    let (position, options) = grid.most_constrained_cell().unwrap();
+    let option = options & !(options - 1);                          // Lowest option
+    grid.set_by_option(
+      position.sub_grid_column,
+      position.sub_grid_row,
+      position.cell_column,
+      position.cell_row,
+      option,
+      SetMethod::User
+    );
+
+    (position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option)
+  }
+
+  #[test]
+  fn it_takes_back_a_user_entry() {
+    let mut grid = puzzle();
+    let (sub_grid_column, sub_grid_row, cell_column, cell_row, _) = set_user_option(&mut grid);
+    assert_ne!(grid.to_options(), puzzle().to_options());
+
+    grid.unfix(sub_grid_column, sub_grid_row, cell_column, cell_row);
+    assert_eq!(grid.to_options(), puzzle().to_options());
+  }
+
+  #[test]
+  fn it_keeps_the_other_user_entries() {
+    let mut grid = puzzle();
+    let (sub_grid_column, sub_grid_row, cell_column, cell_row, _) = set_user_option(&mut grid);
+    let user = set_user_option(&mut grid);
+
+    grid.unfix(sub_grid_column, sub_grid_row, cell_column, cell_row);
+
+    let mut expected = puzzle();
+    expected.set_by_option(user.0, user.1, user.2, user.3, user.4, SetMethod::User);
+    assert_eq!(grid.to_options(), expected.to_options());
+    assert_eq!(grid.get(user.0, user.1).cells[user.3][user.2].set_method, SetMethod::User);
+  }
+
+  #[test]
+  fn it_takes_back_a_given() {
+    let mut grid = puzzle();
+    grid.unfix(2, 0, 0, 0);                                         // '1' in the top row
+
+    let mut puzzle = super::PUZZLE.concat();
+    puzzle.replace_range(4..5, ".");
+    let mut expected = Grid::new(3, 2);
+    expected.load_set_options(&super::set_options(3, 2, &puzzle));
+
+    assert_eq!(grid.to_options(), expected.to_options());
+    assert_eq!(grid.get(2, 0).cells[0][0].set_method, SetMethod::Unset);
+  }
+}
+
+#[cfg(test)]
+mod grid_history {
+  use crate::error::SolverError;
+  use crate::grid::{grid::Grid, History, Position, UserAction};
+  use super::puzzle;
+
+  fn unsolved(grid: &Grid) -> (Position, u64) {
+    grid.most_constrained_cell().unwrap()
+  }
+
+  fn lowest(options: u64) -> u64 {
+    options & !(options - 1)
+  }
+
+  #[test]
+  fn it_undoes_and_redoes_moves() {
+    let mut grid = puzzle();
+    let start = grid.to_options();
+
+    let (position, options) = unsolved(&grid);
+    grid.play(UserAction::RemoveCandidate { position, option: lowest(options) }).unwrap();
+    let removed = grid.to_options();
+
+    let (position, _) = unsolved(&grid);
+    grid.play(UserAction::SetValue { position, symbol: grid.get_cell(&position).symbol() }).unwrap();
+    let set = grid.to_options();
+    assert_eq!(grid.history().len(), 2);
+
+    assert!(grid.undo());
+    assert_eq!(grid.to_options(), removed);
+    assert!(grid.undo());
+    assert_eq!(grid.to_options(), start);
+    assert!(!grid.undo());
+
+    assert!(grid.redo());
+    assert!(grid.redo());
+    assert_eq!(grid.to_options(), set);
+    assert!(!grid.redo());
+  }
+
+  #[test]
+  fn it_jumps_to_any_move() {
+    let mut grid = puzzle();
+    let mut states = vec![grid.to_options()];
+
+    for _ in 0..3 {
+      let (position, options) = unsolved(&grid);
+      grid.play(UserAction::RemoveCandidate { position, option: lowest(options) }).unwrap();
+      states.push(grid.to_options());
+    }
+
+    assert!(grid.jump(1));
+    assert_eq!(grid.to_options(), states[1]);
+    assert!(grid.jump(3));
+    assert_eq!(grid.to_options(), states[3]);
+    assert!(grid.jump(0));
+    assert_eq!(grid.to_options(), states[0]);
+    assert!(!grid.jump(4));
+    assert_eq!(grid.history().current(), 0);
+  }
+
+  #[test]
+  fn it_drops_the_moves_undone_once_another_is_played() {
+    let mut grid = puzzle();
+    let (position, options) = unsolved(&grid);
+    grid.play(UserAction::RemoveCandidate { position, option: lowest(options) }).unwrap();
+    grid.undo();
+
+    grid.play(UserAction::RemoveCandidate { position, option: lowest(options - lowest(options)) }).unwrap();
+    assert_eq!(grid.history().len(), 1);
+    assert!(!grid.history().can_redo());
+  }
+
+  #[test]
+  fn it_only_records_moves_that_change_the_grid() {
+    let mut grid = puzzle();
+    let (position, options) = unsolved(&grid);
+
+    let removed = lowest(!options & ((1 << 6) - 1));                // Already removed
+    grid.play(UserAction::RemoveCandidate { position, option: removed }).unwrap();
+    assert!(grid.history().is_empty());
+
+    let position = Position { sub_grid_column: 3, ..position };
+    assert_eq!(grid.play(UserAction::Unfix(position)), Err(SolverError::InvalidPosition(position)));
+    assert!(grid.history().is_empty());
+  }
+
+  #[test]
+  fn it_undoes_an_unfix() {
+    let mut grid = puzzle();
+    let (position, _) = unsolved(&grid);
+    grid.play(UserAction::SetValue { position, symbol: grid.get_cell(&position).symbol() }).unwrap();
+    let set = grid.to_options();
+
+    grid.play(UserAction::Unfix(position)).unwrap();
+    assert_eq!(grid.to_options(), puzzle().to_options());
+    assert!(grid.undo());
+    assert_eq!(grid.to_options(), set);
+  }
+
+  #[test]
+  fn it_saves_the_history_with_the_game() {
+    let mut grid = puzzle();
+    for _ in 0..2 {
+      let (position, options) = unsolved(&grid);
+      grid.play(UserAction::RemoveCandidate { position, option: lowest(options) }).unwrap();
+    }
+    grid.undo();
+
+    let json = grid.history().to_json();
+    assert!(json.starts_with(r#"{"moves":[{"action":{"removeCandidate":{"position":{"subGridColumn":"#));
+    let history = History::from_json(&json).unwrap();
+    assert_eq!(&history, grid.history());
+
+    let mut saved = Grid::new(3, 2);
+    saved.set_json(&grid.to_json()).unwrap();
+    saved.load_history(history).unwrap();
+    assert!(saved.undo());
+    assert_eq!(saved.to_options(), puzzle().to_options());
+    assert!(saved.jump(2));
+    grid.redo();
+    assert_eq!(saved.to_options(), grid.to_options());
+
+    assert!(History::from_json(r#"{"moves":[],"current":1}"#).is_err());
+  }
+
+  #[test]
+  fn it_rejects_a_history_not_leading_to_the_grid() {
+    let mut grid = puzzle();
+    let position = (0..36).map(|index| grid.position(index % 6, index / 6))
+      .find(|position| grid.get_cell(position).options.count_ones() > 2)
+      .unwrap();
+    let options = grid.get_cell(&position).options;
+    grid.play(UserAction::RemoveCandidate { position, option: lowest(options) }).unwrap();
+    assert_eq!(grid.history().moves()[0].changes.len(), 1);         // Other options left i.e. nothing struck out
+
+    let mut other = puzzle();                                       // Move not played
+    assert!(matches!(other.load_history(grid.history().clone()), Err(SolverError::InvalidHistory(_))));
+    assert!(other.history().is_empty());
+
+    let mut history = grid.history().clone();
+    grid.undo();
+    grid.play(UserAction::RemoveCandidate { position, option: lowest(options - lowest(options)) }).unwrap();
+    assert!(matches!(grid.load_history(history.clone()), Err(SolverError::InvalidHistory(_))));
+
+    history = grid.history().clone();
+    assert_eq!(grid.load_history(history), Ok(()));
+  }
+}
+
+#[cfg(test)]
+mod grid_pencil_marks {
+  use crate::error::SolverError;
+  use crate::grid::{grid::Grid, JsonGrid, OptionChange, Position, UserAction};
+  use super::puzzle;
+
+  #[test]
+  fn it_toggles_marks_without_striking_out() {
+    let mut grid = puzzle();
+    let options = grid.to_options();
+    let (position, _) = grid.most_constrained_cell().unwrap();
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = position;
+
+    grid.toggle_pencil_mark(sub_grid_column, sub_grid_row, cell_column, cell_row, 4).unwrap();
+    grid.toggle_pencil_mark(sub_grid_column, sub_grid_row, cell_column, cell_row, 8).unwrap();
+    grid.toggle_highlight(sub_grid_column, sub_grid_row, cell_column, cell_row, 8).unwrap();
+    grid.toggle_pencil_mark(sub_grid_column, sub_grid_row, cell_column, cell_row, 4).unwrap();
+
+    assert_eq!(grid.get_cell(&position).pencil_marks, 8);
+    assert_eq!(grid.get_cell(&position).highlights, 8);
+    assert_eq!(grid.to_options(), options);
+  }
+
+  #[test]
+  fn it_rejects_a_mark_outside_the_grid() {
+    let mut grid = puzzle();
+
+    assert_eq!(
+      grid.toggle_pencil_mark(3, 0, 0, 0, 4),
+      Err(SolverError::InvalidPosition(Position { sub_grid_column: 3, sub_grid_row: 0, cell_column: 0, cell_row: 0 }))
+    );
+    assert_eq!(grid.toggle_highlight(0, 0, 0, 0, 64), Err(SolverError::InvalidOption { index: 0, option: 64 }));
+    assert_eq!(grid.toggle_pencil_mark(0, 0, 0, 0, 6), Err(SolverError::InvalidOption { index: 0, option: 6 }));
+  }
+
+  #[test]
+  fn it_finds_marks_that_are_not_candidates() {
+    let mut grid = puzzle();
+    let (position, options) = grid.most_constrained_cell().unwrap();
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = position;
+    let missing = !options & 63;
+    let removed = missing & !(missing - 1);                         // Lowest option already removed
+    let candidate = options & !(options - 1);
+
+    grid.toggle_pencil_mark(sub_grid_column, sub_grid_row, cell_column, cell_row, candidate).unwrap();
+    assert!(grid.pencil_mark_mistakes().is_empty());
+
+    grid.toggle_pencil_mark(sub_grid_column, sub_grid_row, cell_column, cell_row, removed).unwrap();
+    assert_eq!(grid.pencil_mark_mistakes(), [OptionChange { position, options: removed }]);
+  }
+
+  #[test]
+  fn it_undoes_a_pencil_mark() {
+    let mut grid = puzzle();
+    let (position, _) = grid.most_constrained_cell().unwrap();
+
+    grid.play(UserAction::TogglePencilMark { position, option: 2 }).unwrap();
+    assert_eq!(grid.get_cell(&position).pencil_marks, 2);
+    assert_eq!(grid.history().moves()[0].changes.len(), 1);
+
+    grid.undo();
+    assert_eq!(grid.get_cell(&position).pencil_marks, 0);
+    grid.redo();
+    assert_eq!(grid.get_cell(&position).pencil_marks, 2);
+  }
+
+  #[test]
+  fn it_keeps_marks_in_the_snapshot_and_when_unfixing() {
+    let mut grid = puzzle();
+    let (position, _) = grid.most_constrained_cell().unwrap();
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = position;
+    grid.toggle_pencil_mark(sub_grid_column, sub_grid_row, cell_column, cell_row, 1).unwrap();
+    grid.toggle_highlight(sub_grid_column, sub_grid_row, cell_column, cell_row, 32).unwrap();
+
+    let mut copy = Grid::new(3, 2);
+    copy.set_json(&JsonGrid::from_json(&grid.to_json().to_json()).unwrap()).unwrap();
+    assert_eq!(copy.get_cell(&position).pencil_marks, 1);
+    assert_eq!(copy.get_cell(&position).highlights, 32);
+
+    grid.unfix(2, 0, 0, 0);                                         // '1' in the top row
+    assert_eq!(grid.get_cell(&position).pencil_marks, 1);
+    assert_eq!(grid.get_cell(&position).highlights, 32);
+  }
+}
+
+#[cfg(test)]
+mod grid_mistakes {
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+  use crate::grid::{grid::Grid, Mistake, Position, UserAction};
+  use super::puzzle;
+
+  // Unsolved cell with its solution and another candidate
+  fn unsolved(grid: &Grid) -> (Position, u64, u64) {
+    let (position, options) = grid.most_constrained_cell().unwrap();
+    let solution = grid.count_solutions(1).grids[0].get_cell(&position).options;
+
+    (position, solution, options & !solution & !(options & !solution).wrapping_sub(1))
+  }
+
+  fn set(grid: &mut Grid, position: &Position, option: u64) {
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = *position;
+    grid.set_by_option(sub_grid_column, sub_grid_row, cell_column, cell_row, option, SetMethod::User);
+  }
+
+  #[test]
+  fn it_accepts_correct_entries() {
+    let mut grid = puzzle();
+    assert_eq!(grid.check_user_entries(), Ok(vec![]));
+
+    let (position, solution, _) = unsolved(&grid);
+    set(&mut grid, &position, solution);
+    assert_eq!(grid.check_user_entries(), Ok(vec![]));
+  }
+
+  #[test]
+  fn it_finds_a_wrong_value() {
+    let mut grid = puzzle();
+    let (position, solution, other) = unsolved(&grid);
+    set(&mut grid, &position, other);
+
+    assert_eq!(grid.check_user_entries(), Ok(vec![Mistake::WrongValue { position, option: other, solution }]));
+  }
+
+  #[test]
+  fn it_finds_a_removed_solution() {
+    let mut grid = puzzle();
+    let (position, solution, other) = unsolved(&grid);
+    grid.play(UserAction::RemoveCandidate { position, option: solution }).unwrap();
+    assert_eq!(grid.check_user_entries(), Ok(vec![Mistake::RemovedSolution { position, option: solution }]));
+
+    grid.undo();
+    grid.play(UserAction::RemoveCandidate { position, option: other }).unwrap();
+    assert_eq!(grid.check_user_entries(), Ok(vec![]));
+  }
+
+  #[test]
+  fn it_rejects_givens_without_a_unique_solution() {
+    let grid = Grid::new(2, 2);
+
+    assert_eq!(grid.check_user_entries(), Err(SolverError::NotUnique(2)));
+  }
+}
+
+#[cfg(test)]
+mod grid_diagonal {
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, Contradiction, House, Position, Variant};
+
+  fn diagonal(columns: usize, rows: usize) -> Grid {
+    let mut grid = Grid::new(columns, rows);
+    grid.set_variant(Variant::Diagonal);
+    grid
+  }
+
+  #[test]
+  fn it_strikes_out_along_the_diagonals() {
+    let mut grid = diagonal(2, 2);
+    grid.set_by_symbol(0, 0, 0, 0, '1', SetMethod::Loaded);
+
+    assert!(!grid.get_cell(&grid.position(3, 3)).contains_option(1));
+    assert!(grid.get_cell(&grid.position(2, 1)).contains_option(1));  // Anti-diagonal
+
+    let mut standard = Grid::new(2, 2);
+    standard.set_by_symbol(0, 0, 0, 0, '1', SetMethod::Loaded);
+    assert!(standard.get_cell(&standard.position(3, 3)).contains_option(1));
+  }
+
+  #[test]
+  fn it_solves_only_with_the_diagonals() {
+    let puzzles = [
+      (2, 2, "...........1.143", "1234341243212143"),
+      (3, 2, ".....................3.....26....145", "123456541632362514654321415263236145")
+    ];
+
+    for &(columns, rows, puzzle, solution) in puzzles.iter() {
+      let options = format::parse(columns, rows, puzzle).unwrap();
+
+      let mut standard = Grid::new(columns, rows);
+      standard.load_set_options(&options);
+      assert_eq!(standard.count_solutions(2).total, 2);
+
+      let mut grid = diagonal(columns, rows);
+      grid.load_set_options(&options);
+      assert!(grid.solve());
+      assert_eq!(grid.to_line(), solution);
+    }
+  }
+
+  #[test]
+  fn it_finds_a_duplicate_on_a_diagonal() {
+    let options = format::parse(2, 2, "1..............1").unwrap();
+
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&options);
+    grid.set_variant(Variant::Diagonal);
+    match grid.check_consistency() {
+      Some(Contradiction::DuplicateOption { house, positions, .. }) => {
+        assert_eq!(house, House::Diagonal(0));
+        assert_eq!(positions, vec![grid.position(0, 0), grid.position(3, 3)]);
+      },
+      contradiction => panic!("Unexpected {:?}", contradiction)
+    }
+
+    assert_eq!(
+      diagonal(2, 2).try_load_set_options(&options),
+      Err(SolverError::DuplicateGiven {
+        position: Position { sub_grid_column: 1, sub_grid_row: 1, cell_column: 1, cell_row: 1 },
+        symbol: '1'
+      })
+    );
+  }
+}
+
+#[cfg(test)]
+mod grid_houses {
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, Contradiction, House, OptionChange, Technique};
+
+  const REGIONS: &str = concat!(
+    "a a a b b b\n",
+    "a a c b b b\n",
+    "a c c c d d\n",
+    "e c c f d d\n",
+    "e e f f f d\n",
+    "e e e f f d\n"
+  );
+
+  const WINDOW: &str = concat!(
+    ". . . .\n",
+    ". x x .\n",
+    ". x x .\n",
+    ". . . .\n"
+  );
+
+  fn jigsaw() -> Grid {
+    let mut grid = Grid::new(3, 2);
+    grid.set_regions(format::parse_houses(3, 2, REGIONS).unwrap()).unwrap();
+    grid
+  }
+
+  fn windowed() -> Grid {
+    let mut grid = Grid::new(2, 2);
+    for positions in format::parse_houses(2, 2, WINDOW).unwrap().into_iter() {
+      grid.add_house(positions).unwrap();
+    }
+    grid
+  }
+
+  #[test]
+  fn it_solves_a_jigsaw() {
+    let options = format::parse(3, 2, "..............4.....2....4...1.612.5").unwrap();
+
+    let mut standard = Grid::new(3, 2);
+    standard.load_set_options(&options);
+    assert_eq!(standard.count_solutions(2).total, 0);             // Givens repeated within the rectangular sub-grids
+
+    let mut grid = jigsaw();
+    grid.load_set_options(&options);
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), "123456456312614523532164245631361245");
+  }
+
+  #[test]
+  fn it_finds_a_duplicate_in_a_region() {
+    let mut grid = jigsaw();
+    grid.load_set_options(&format::parse(3, 2, "1......1............................").unwrap());
+
+    match grid.check_consistency() {
+      Some(Contradiction::DuplicateOption { house, .. }) => assert_eq!(house, House::Region(0)),
+      contradiction => panic!("Unexpected {:?}", contradiction)
+    }
+  }
+
+  #[test]
+  fn it_solves_with_an_extra_house() {
+    let options = format::parse(2, 2, ".........3.1.14.").unwrap();
+
+    let mut standard = Grid::new(2, 2);
+    standard.load_set_options(&options);
+    assert_eq!(standard.count_solutions(2).total, 2);
+
+    let mut grid = windowed();
+    grid.load_set_options(&options);
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), "1234341243212143");
+  }
+
+  #[test]
+  fn it_removes_an_option_limited_to_where_houses_meet() {
+    let mut grid = windowed();
+    let mut options = vec![15; 16];
+    for &(column, row) in [(1, 2), (2, 2)].iter() {                 // 1 only in the window's top row
+      options[grid.options_index(&grid.position(column, row))] = 14;
+    }
+    grid.load_options(&options);
+
+    let step = grid.next_step().unwrap();
+    assert_eq!(step.technique, Technique::BoxLineReduction);
+    assert_eq!(step.house, Some(House::Extra(0)));
+    assert_eq!(step.cells, vec![grid.position(1, 1), grid.position(2, 1)]);
+    assert_eq!(step.removed, vec![
+      OptionChange { position: grid.position(0, 1), options: 1 },
+      OptionChange { position: grid.position(3, 1), options: 1 }
+    ]);
+  }
+
+  #[test]
+  fn it_rejects_invalid_houses() {
+    let mut grid = Grid::new(2, 2);
+    let mut regions = format::parse_houses(2, 2, "aabb aabb ccdd ccdd").unwrap();
+    regions.pop();
+    assert!(matches!(grid.set_regions(regions.clone()), Err(SolverError::InvalidHouse(_))));
+
+    regions.push(regions[0].clone());                               // Overlapping
+    assert!(matches!(grid.set_regions(regions), Err(SolverError::InvalidHouse(_))));
+
+    let mut positions = grid.get_house_positions(&House::Row(0));
+    positions[3] = positions[0];
+    assert!(matches!(grid.add_house(positions), Err(SolverError::InvalidHouse(_))));
+    assert!(grid.regions().is_empty() && grid.extra_houses().is_empty());
+  }
+}
+
+#[cfg(test)]
+mod grid_rules {
+  use crate::cell::SetMethod;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, Contradiction, Rule};
+
+  fn with_rule(columns: usize, rows: usize, rule: Rule) -> Grid {
+    let mut grid = Grid::new(columns, rows);
+    grid.add_rule(rule);
+    grid
+  }
+
+  #[test]
+  fn it_strikes_out_a_knight_move_apart() {
+    let mut grid = with_rule(2, 2, Rule::AntiKnight);
+    grid.set_by_symbol(0, 0, 0, 0, '1', SetMethod::Loaded);
+
+    assert!(!grid.get_cell(&grid.position(1, 2)).contains_option(1));
+    assert!(!grid.get_cell(&grid.position(2, 1)).contains_option(1));
+    assert!(grid.get_cell(&grid.position(3, 3)).contains_option(1));
+  }
+
+  #[test]
+  fn it_strikes_out_consecutive_symbols() {
+    let mut grid = with_rule(3, 2, Rule::NonConsecutive);
+    grid.set_by_symbol(0, 0, 0, 0, '3', SetMethod::Loaded);
+
+    for &(column, row) in [(1, 0), (0, 1)].iter() {
+      let options = grid.get_cell(&grid.position(column, row)).options;
+      assert_eq!(options, 0b110001);                                // 1, 5 and 6 left
+    }
+    assert_eq!(grid.get_cell(&grid.position(1, 1)).options, 0b111011);  // Diagonal i.e. only the sub-grid's 3 removed
+  }
+
+  #[test]
+  fn it_solves_only_with_the_rule() {
+    let puzzles = [
+      (Rule::AntiKnight, 2, 2, ".............143", "1234432134122143"),
+      (Rule::AntiKing, 3, 2, "..........12................25..2143", "123456345612561234234561416325652143"),
+      (Rule::NonConsecutive, 3, 2, "...........4.....................2.3", "142536526314364152631425253641415263")
+    ];
+
+    for &(rule, columns, rows, puzzle, solution) in puzzles.iter() {
+      let options = format::parse(columns, rows, puzzle).unwrap();
+
+      let mut standard = Grid::new(columns, rows);
+      standard.load_set_options(&options);
+      assert_eq!(standard.count_solutions(2).total, 2);
+
+      let mut grid = with_rule(columns, rows, rule);
+      grid.load_set_options(&options);
+      assert!(grid.solve());
+      assert_eq!(grid.to_line(), solution);
+    }
+  }
+
+  #[test]
+  fn it_finds_a_broken_rule() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&format::parse(2, 2, "1.....1.........").unwrap());
+    assert_eq!(grid.check_consistency(), None);
+
+    grid.add_rule(Rule::AntiKnight);
+    assert_eq!(
+      grid.check_consistency(),
+      Some(Contradiction::BrokenRule { rule: Rule::AntiKnight, positions: vec![grid.position(0, 0), grid.position(2, 1)] })
+    );
+
+    assert_eq!(with_rule(2, 2, Rule::AntiKing).count_solutions(1).total, 0);  // Too small for a solution
+  }
+}
+
+#[cfg(test)]
+mod grid_edges {
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, Contradiction, Edge, EdgeClue};
+
+  fn edge(grid: &Grid, clue: EdgeClue, first: (usize, usize), second: (usize, usize)) -> Edge {
+    Edge { clue, first: grid.position(first.0, first.1), second: grid.position(second.0, second.1) }
+  }
+
+  // Every edge of the solution matching one of the clues, the first listed winning e.g. 1 and 2 get a white dot
+  fn edges(grid: &Grid, solution: &str, clues: &[EdgeClue]) -> Vec<Edge> {
+    let size = (solution.len() as f64).sqrt() as usize;
+    let values: Vec<u64> = solution.chars().map(|symbol| 1 << (symbol.to_digit(10).unwrap() - 1)).collect();
+
+    let mut edges = vec![];
+    for row in 0..size {
+      for column in 0..size {
+        for &(next_column, next_row) in [(column + 1, row), (column, row + 1)].iter() {
+          if next_column < size && next_row < size {
+            let (first, second) = (values[row * size + column], values[next_row * size + next_column]);
+            if let Some(&clue) = clues.iter().find(|clue| clue.options(first, size) & second > 0) {
+              edges.push(edge(grid, clue, (column, row), (next_column, next_row)));
+            }
+          }
+        }
+      }
+    }
+
+    edges
+  }
+
+  #[test]
+  fn it_strikes_out_across_an_edge() {
+    let mut grid = Grid::new(2, 2);
+    grid.add_edge(edge(&grid, EdgeClue::BlackDot, (0, 0), (1, 0))).unwrap();
+    grid.add_edge(edge(&grid, EdgeClue::V, (0, 0), (0, 1))).unwrap();
+    grid.set_by_symbol(0, 0, 0, 0, '2', SetMethod::Loaded);
+
+    assert_eq!(grid.get_cell(&grid.position(1, 0)).options, 1 + 8);  // 1 or 4
+    assert_eq!(grid.get_cell(&grid.position(0, 1)).options, 4);      // 3
+  }
+
+  #[test]
+  fn it_keeps_options_with_a_partner() {
+    let mut grid = Grid::new(3, 2);
+    grid.add_edge(edge(&grid, EdgeClue::X, (0, 0), (1, 0))).unwrap();
+    grid.add_edge(edge(&grid, EdgeClue::BlackDot, (3, 3), (3, 4))).unwrap();
+    grid.simplify();
+
+    for &(column, row) in [(0, 0), (1, 0)].iter() {
+      assert_eq!(grid.get_cell(&grid.position(column, row)).options, 0b111000);  // 4 + 6 or 5 + 5 within a row
+    }
+    assert_eq!(grid.get_cell(&grid.position(3, 4)).options, 0b101111);  // No double or half of 5
+  }
+
+  #[test]
+  fn it_strikes_out_by_a_negative_clue() {
+    let mut grid = Grid::new(3, 2);
+    grid.add_edge(edge(&grid, EdgeClue::WhiteDot, (0, 0), (1, 0))).unwrap();
+    grid.add_negative_clue(EdgeClue::WhiteDot);
+    grid.add_negative_clue(EdgeClue::WhiteDot);
+    grid.set_by_symbol(0, 0, 0, 0, '3', SetMethod::Loaded);
+
+    assert_eq!(grid.negative_clues(), &[EdgeClue::WhiteDot]);
+    assert_eq!(grid.get_cell(&grid.position(1, 0)).options, 0b001010);  // 2 or 4
+    assert_eq!(grid.get_cell(&grid.position(0, 1)).options, 0b110001);  // Neither 2 nor 4 without a dot
+  }
+
+  #[test]
+  fn it_solves_with_edges() {
+    let solution = "123456541632362514654321415263236145";
+    let mut grid = Grid::new(3, 2);
+    for item in edges(&grid, solution, &[EdgeClue::WhiteDot, EdgeClue::BlackDot]).into_iter() {
+      grid.add_edge(item).unwrap();
+    }
+    grid.add_negative_clue(EdgeClue::WhiteDot);
+    grid.add_negative_clue(EdgeClue::BlackDot);
+
+    assert!(grid.count_solutions(2).unique());                      // No givens at all
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), solution);
+
+    let solution = "1234341221434321";
+    let options = format::parse(2, 2, "..............21").unwrap();
+    let mut standard = Grid::new(2, 2);
+    standard.load_set_options(&options);
+    assert_eq!(standard.count_solutions(2).total, 2);
+
+    let mut grid = Grid::new(2, 2);
+    for item in edges(&grid, solution, &[EdgeClue::X, EdgeClue::V]).into_iter() {
+      grid.add_edge(item).unwrap();
+    }
+    grid.load_set_options(&options);
+    assert!(grid.solve());
+    assert_eq!(grid.to_line(), solution);
+  }
+
+  #[test]
+  fn it_finds_a_broken_clue() {
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&format::parse(2, 2, "12..............").unwrap());
+    grid.add_negative_clue(EdgeClue::WhiteDot);
+    assert_eq!(
+      grid.check_consistency(),
+      Some(Contradiction::BrokenClue { clue: EdgeClue::WhiteDot, negative: true, positions: vec![grid.position(0, 0), grid.position(1, 0)] })
+    );
+
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&format::parse(2, 2, "13..............").unwrap());
+    grid.add_edge(edge(&grid, EdgeClue::BlackDot, (0, 0), (1, 0))).unwrap();
+    let positions = vec![grid.position(0, 0), grid.position(1, 0)];
+    assert_eq!(grid.check_consistency(), Some(Contradiction::BrokenClue { clue: EdgeClue::BlackDot, negative: false, positions }));
+  }
+
+  #[test]
+  fn it_rejects_invalid_edges() {
+    let mut grid = Grid::new(2, 2);
+
+    let diagonal = edge(&grid, EdgeClue::X, (0, 0), (1, 1));
+    assert!(matches!(grid.add_edge(diagonal), Err(SolverError::InvalidConstraint(_))));
+    let mut outside = edge(&grid, EdgeClue::V, (3, 0), (3, 1));
+    outside.second.sub_grid_column = 2;
+    assert_eq!(grid.add_edge(outside), Err(SolverError::InvalidPosition(outside.second)));
+    assert!(grid.edges().is_empty());
+  }
+}
+
+#[cfg(test)]
+mod grid_option_bits {
+  use crate::cell::{SetMethod, SYMBOLS};
+  use crate::grid::grid::{Grid, LargeGrid, SmallGrid};
+  use crate::utils::bit_utils::OptionBits;
+
+  const PUZZLE: &str = "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+  const SOLUTION: &str = "417369825632158947958724316825437169791586432346912758289643571573291684164875293";
+
+  #[test]
+  fn it_solves_the_same_with_any_option_bits() {
+    let mut small_grid: SmallGrid = PUZZLE.parse().unwrap();
+    let mut grid: Grid = PUZZLE.parse().unwrap();
+    let mut large_grid: LargeGrid = PUZZLE.parse().unwrap();
+
+    assert!(small_grid.solve_exhaustive());
+    assert!(grid.solve_exhaustive());
+    assert!(large_grid.solve_exhaustive());
+    assert_eq!(small_grid.to_line(), SOLUTION);
+    assert_eq!(grid.to_line(), SOLUTION);
+    assert_eq!(large_grid.to_line(), SOLUTION);
+  }
+
+  #[test]
+  fn it_strikes_out_the_64th_symbol() {
+    let mut grid = Grid::new(8, 8);
+    grid.set_by_symbol(0, 0, 0, 0, SYMBOLS[63], SetMethod::Loaded); // Highest bit of a u64
+
+    assert_eq!(grid.get_cell(&grid.position(0, 0)).options, 1 << 63);
+    assert_eq!(grid.get_cell(&grid.position(1, 0)).options, u64::MAX >> 1);
+    assert_eq!(grid.get_cell(&grid.position(63, 63)).options, u64::MAX);
+  }
+
+  #[test]
+  fn it_strikes_out_the_100th_symbol() {
+    let mut grid = LargeGrid::new(10, 10);
+    grid.set_by_symbol(0, 0, 0, 0, SYMBOLS[99], SetMethod::Loaded);
+
+    assert_eq!(grid.get_cell(&grid.position(0, 0)).options, 1 << 99);
+    assert_eq!(grid.get_cell(&grid.position(0, 1)).options, u128::mask(99));
+    assert_eq!(grid.get_cell(&grid.position(99, 99)).options, u128::mask(100));
+  }
+}
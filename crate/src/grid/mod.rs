@@ -1,9 +1,519 @@
+use std::fmt::{self, Display};
+use wasm_bindgen::prelude::*;
+use serde::{Serialize, Deserialize};
+
 pub mod grid;
 
+use grid::GridOf;
+use crate::error::SolverError;
+use crate::sub_grid::JsonSubGrid;
+use crate::cell::CellState;
+use crate::utils::bit_utils::OptionBits;
+
 mod grid_test;
 
-pub struct CellOptions {
+pub struct CellOptions<T: OptionBits = u64> {
   pub column: usize,
   pub row: usize,
-  pub options: u64
+  pub options: T
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+  pub sub_grid_column: usize,
+  pub sub_grid_row: usize,
+  pub cell_column: usize,
+  pub cell_row: usize
+}
+
+impl Display for Position {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      formatter,
+      "sub-grid ({}, {}), cell ({}, {})",
+      self.sub_grid_column,
+      self.sub_grid_row,
+      self.cell_column,
+      self.cell_row
+    )
+  }
+}
+
+// Houses each symbol must appear in once, on top of the columns, rows and sub-grids
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Variant {
+  Standard,
+  Diagonal                                                          // X-sudoku i.e. both main diagonals
+}
+
+// Rule between pairs of cells a move apart, removing options from one when the other is set
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Rule {
+  AntiKnight,                                                       // No symbol repeated a chess knight's move apart
+  AntiKing,                                                         // No symbol repeated a chess king's move apart
+  NonConsecutive                                                    // No consecutive symbols in orthogonal neighbours
+}
+
+impl Display for Rule {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Rule::AntiKnight => write!(formatter, "anti-knight"),
+      Rule::AntiKing => write!(formatter, "anti-king"),
+      Rule::NonConsecutive => write!(formatter, "non-consecutive")
+    }
+  }
+}
+
+// Clue on the edge between orthogonally adjacent cells
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EdgeClue {
+  WhiteDot,                                                         // Consecutive values
+  BlackDot,                                                         // One value double the other
+  X,                                                                // Values adding up to 10
+  V                                                                 // Values adding up to 5
+}
+
+impl EdgeClue {
+  // Options of the values across the edge from any of the options
+  pub fn options<T: OptionBits>(&self, options: T, max_options: usize) -> T {
+    let mut across = T::ZERO;
+    for bit in (0..max_options).filter(|&bit| options & T::bit(bit) != T::ZERO) {
+      let value = bit + 1;
+      let values = match self {
+        EdgeClue::WhiteDot => vec![value - 1, value + 1],
+        EdgeClue::BlackDot => if value % 2 == 0 { vec![value / 2, value * 2] } else { vec![value * 2] },
+        EdgeClue::X => vec![10usize.saturating_sub(value)],
+        EdgeClue::V => vec![5usize.saturating_sub(value)]
+      };
+
+      for other in values.into_iter().filter(|&x| x > 0 && x <= max_options) {
+        across |= T::bit(other - 1);
+      }
+    }
+
+    across
+  }
+
+  // Dots and XV clues are given separately i.e. a dot's absence is a clue for the dots only
+  pub fn dot(&self) -> bool {
+    matches!(self, EdgeClue::WhiteDot | EdgeClue::BlackDot)
+  }
+}
+
+impl Display for EdgeClue {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      EdgeClue::WhiteDot => write!(formatter, "white dot"),
+      EdgeClue::BlackDot => write!(formatter, "black dot"),
+      EdgeClue::X => write!(formatter, "X"),
+      EdgeClue::V => write!(formatter, "V")
+    }
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+  pub clue: EdgeClue,
+  pub first: Position,
+  pub second: Position
+}
+
+impl Edge {
+  pub fn joins(&self, position: &Position, other: &Position) -> bool {
+    (self.first == *position && self.second == *other) || (self.first == *other && self.second == *position)
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct Solutions<T: OptionBits = u64> {
+  pub total: usize,                                                 // Stops counting at the limit searched for
+  pub guesses: usize,                                               // Options tried, up to max_guesses
+  pub max_guesses: usize,                                           // Only limited by try_count_solutions
+  pub grids: Vec<GridOf<T>>                                         // First two distinct solutions found
+}
+
+impl<T: OptionBits> Solutions<T> {
+  pub fn new() -> Self {
+    Self {
+      total: 0,
+      guesses: 0,
+      max_guesses: usize::MAX,
+      grids: Vec::with_capacity(2)
+    }
+  }
+
+  pub fn add(&mut self, solution: GridOf<T>) {
+    if self.grids.len() < 2 {
+      self.grids.push(solution);
+    }
+    self.total += 1;
+  }
+
+  pub fn unique(&self) -> bool {
+    self.total == 1
+  }
+}
+
+impl<T: OptionBits> Default for Solutions<T> {
+  fn default() -> Self {
+    Self::new()                                                     // Not 0 max_guesses
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum House {
+  Column(usize),
+  Row(usize),
+  SubGrid(usize, usize),                                            // sub-grid column, sub-grid row
+  Diagonal(usize),                                                  // 0 top left to bottom right, 1 top right to bottom left
+  Region(usize),                                                    // Jigsaw region replacing the sub-grids
+  Extra(usize)                                                      // Extra house added to the grid e.g. a Windoku window
+}
+
+impl Display for House {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      House::Column(column) => write!(formatter, "column {}", column),
+      House::Row(row) => write!(formatter, "row {}", row),
+      House::SubGrid(column, row) => write!(formatter, "sub-grid ({}, {})", column, row),
+      House::Diagonal(diagonal) => write!(formatter, "diagonal {}", diagonal),
+      House::Region(region) => write!(formatter, "region {}", region),
+      House::Extra(house) => write!(formatter, "extra house {}", house)
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Contradiction<T: OptionBits = u64> {
+  NoOptions(Position),                                              // Cell with all of its options removed
+  DuplicateOption { house: House, option: T, positions: Vec<Position> },  // Cells set to the same option
+  MissingOption { house: House, option: T, positions: Vec<Position> },  // No cell left in the house for the option
+  OptionRemoved { position: Position, option: T },                  // Setting an option already removed from the cell
+  BrokenConstraint { constraint: usize, positions: Vec<Position> }, // Index of the grid's constraint
+  BrokenRule { rule: Rule, positions: Vec<Position> },              // Pair of solved cells
+  BrokenClue { clue: EdgeClue, negative: bool, positions: Vec<Position> }  // Pair of solved cells, negative if no edge
+}
+
+impl<T: OptionBits> Contradiction<T> {
+  // Cells to highlight
+  pub fn positions(&self) -> Vec<Position> {
+    match self {
+      Contradiction::NoOptions(position) => vec![*position],
+      Contradiction::DuplicateOption { positions, .. } => positions.clone(),
+      Contradiction::MissingOption { positions, .. } => positions.clone(),
+      Contradiction::OptionRemoved { position, .. } => vec![*position],
+      Contradiction::BrokenConstraint { positions, .. } => positions.clone(),
+      Contradiction::BrokenRule { positions, .. } => positions.clone(),
+      Contradiction::BrokenClue { positions, .. } => positions.clone()
+    }
+  }
+}
+
+impl<T: OptionBits> Display for Contradiction<T> {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Contradiction::NoOptions(position) => write!(formatter, "No options left at {}", position),
+      Contradiction::DuplicateOption { house, option, .. } =>
+        write!(formatter, "Option {} set more than once in {}", option, house),
+      Contradiction::MissingOption { house, option, .. } =>
+        write!(formatter, "No place left for option {} in {}", option, house),
+      Contradiction::OptionRemoved { position, option } =>
+        write!(formatter, "Option {} already removed from {}", option, position),
+      Contradiction::BrokenConstraint { constraint, positions } =>
+        write!(formatter, "Constraint {} broken by {} cells", constraint, positions.len()),
+      Contradiction::BrokenRule { rule, positions } =>
+        write!(formatter, "The {} rule broken by {} cells", rule, positions.len()),
+      Contradiction::BrokenClue { clue, negative: false, .. } => write!(formatter, "The {} clue broken", clue),
+      Contradiction::BrokenClue { clue, negative: true, .. } => write!(formatter, "Cells without a {} clue match it", clue)
+    }
+  }
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Technique {                                                // Simplest first
+  NakedSingle,                                                      // Only one option left in the cell
+  HiddenSingle,                                                     // Option left in only one cell of a house
+  NakedSubset,                                                      // n cells of a house limited to the same n options
+  HiddenSubset,                                                     // n options of a house limited to the same n cells
+  BoxLineReduction,                                                 // Option in a sub-grid limited to one column/row or vice versa
+  Trial                                                             // Option leads to a contradiction
+}
+
+impl Display for Technique {
+  fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    let name = match self {
+      Technique::NakedSingle => "naked single",
+      Technique::HiddenSingle => "hidden single",
+      Technique::NakedSubset => "naked subset",
+      Technique::HiddenSubset => "hidden subset",
+      Technique::BoxLineReduction => "box/line reduction",
+      Technique::Trial => "elimination by trial"
+    };
+
+    write!(formatter, "{}", name)
+  }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: OptionBits")]
+pub struct OptionChange<T: OptionBits = u64> {
+  pub position: Position,
+  pub options: T                                                    // Option set or options removed
+}
+
+// Next logical deduction, applied to the grid only by Grid::apply_step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step<T: OptionBits = u64> {
+  pub technique: Technique,
+  pub house: Option<House>,                                         // House the deduction was made in (not for trial)
+  pub cells: Vec<Position>,                                         // Cells the deduction is based on
+  pub set: Vec<OptionChange<T>>,                                    // Cells left with a single option
+  pub removed: Vec<OptionChange<T>>                                 // Options removed from the other cells
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceAction {
+  SetByOption,
+  StrikeOut,                                                        // Option of a solved cell removed from the other cells
+  RemoveOption
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Reason {
+  Given,                                                            // Loaded with the puzzle
+  User,                                                             // Set or removed by the caller
+  LastOption,                                                       // Only option left in the cell
+  OnlyOption(House),                                                // Option left in only one cell of the house
+  LimitedOptions(House),                                            // Options limited to some cells of the house
+  BoxLine(House),                                                   // Option limited to where the house and another house meet
+  Trial,                                                            // Other option resulted in an invalid grid
+  Constraint(usize),                                                // Index of the grid's constraint
+  Edge(usize),                                                      // Index of the grid's edge clue
+  Guess                                                             // Option tried by the exhaustive search
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: OptionBits")]
+pub struct TraceEntry<T: OptionBits = u64> {
+  pub action: TraceAction,
+  pub position: Position,
+  pub options: T,                                                   // Option set, struck out or removed
+  pub technique: Option<Technique>,                                 // Technique that started the chain of changes
+  pub reason: Reason,
+  pub removed: Vec<OptionChange<T>>                                 // Options removed from each cell
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cause {
+  pub technique: Option<Technique>,
+  pub reason: Reason
+}
+
+// Changes made to a grid, in order, once Grid::enable_trace called
+pub type Trace = TraceOf<u64>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceOf<T: OptionBits> {
+  entries: Vec<TraceEntry<T>>,
+  cause: Cause                                                      // Recorded with the next changes
+}
+
+impl<T: OptionBits> TraceOf<T> {
+  pub fn new() -> Self {
+    Self {
+      entries: Vec::new(),
+      cause: Cause { technique: None, reason: Reason::User }
+    }
+  }
+
+  pub fn iter(&self) -> std::slice::Iter<'_, TraceEntry<T>> {
+    self.entries.iter()
+  }
+
+  pub fn len(&self) -> usize {
+    self.entries.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.entries.is_empty()
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(&self.entries).expect("Error writing trace")
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, SolverError<T>> {
+    let entries = serde_json::from_str(json).map_err(|error| SolverError::InvalidJson(error.to_string()))?;
+
+    Ok(Self { entries, ..Self::new() })
+  }
+}
+
+impl<T: OptionBits> Default for TraceOf<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+impl<T: OptionBits> IntoIterator for TraceOf<T> {
+  type Item = TraceEntry<T>;
+  type IntoIter = std::vec::IntoIter<TraceEntry<T>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.entries.into_iter()
+  }
+}
+
+impl<'a, T: OptionBits> IntoIterator for &'a TraceOf<T> {
+  type Item = &'a TraceEntry<T>;
+  type IntoIter = std::slice::Iter<'a, TraceEntry<T>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.entries.iter()
+  }
+}
+
+// Move made by a player, recorded in the grid's history by Grid::play
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "T: OptionBits")]
+pub enum UserAction<T: OptionBits = u64> {
+  SetValue { position: Position, symbol: char },
+  RemoveCandidate { position: Position, option: T },
+  TogglePencilMark { position: Position, option: T },
+  Unfix(Position)                                                   // Take back a set value
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: OptionBits")]
+pub struct CellChange<T: OptionBits = u64> {
+  pub position: Position,
+  pub before: CellState<T>,
+  pub after: CellState<T>
+}
+
+// Action with only the cells it changed, including those struck out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: OptionBits")]
+pub struct Move<T: OptionBits = u64> {
+  pub action: UserAction<T>,
+  pub changes: Vec<CellChange<T>>
+}
+
+// Moves played in order - those after the current move were undone and can be redone until the next move is played
+pub type History = HistoryOf<u64>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: OptionBits")]
+pub struct HistoryOf<T: OptionBits> {
+  moves: Vec<Move<T>>,
+  current: usize                                                    // Number of moves applied to the grid
+}
+
+impl<T: OptionBits> HistoryOf<T> {
+  pub fn new() -> Self {
+    Self {
+      moves: Vec::new(),
+      current: 0
+    }
+  }
+
+  pub fn moves(&self) -> &[Move<T>] {
+    &self.moves
+  }
+
+  pub fn current(&self) -> usize {
+    self.current
+  }
+
+  pub fn len(&self) -> usize {
+    self.moves.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.moves.is_empty()
+  }
+
+  pub fn can_undo(&self) -> bool {
+    self.current > 0
+  }
+
+  pub fn can_redo(&self) -> bool {
+    self.current < self.moves.len()
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("Error writing history")
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, SolverError<T>> {
+    let history: Self = serde_json::from_str(json).map_err(|error| SolverError::InvalidJson(error.to_string()))?;
+    if history.current > history.moves.len() {
+      return Err(SolverError::InvalidJson(format!("Current move {} after the last move", history.current)));
+    }
+
+    Ok(history)
+  }
+
+  fn push(&mut self, played: Move<T>) {
+    self.moves.truncate(self.current);                              // Moves undone can no longer be redone
+    self.moves.push(played);
+    self.current += 1;
+  }
+}
+
+impl<T: OptionBits> Default for HistoryOf<T> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+// User entry that doesn't match the solution, found by Grid::check_user_entries
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", bound = "T: OptionBits")]
+pub enum Mistake<T: OptionBits = u64> {
+  WrongValue { position: Position, option: T, solution: T },        // Value set by the user
+  RemovedSolution { position: Position, option: T }                 // Candidate removed by a move played
+}
+
+impl<T: OptionBits> Mistake<T> {
+  pub fn position(&self) -> Position {
+    match self {
+      Mistake::WrongValue { position, .. } => *position,
+      Mistake::RemovedSolution { position, .. } => *position
+    }
+  }
+}
+
+// Snapshot of the whole grid by sub-grid rows (IJsonGrid), so the app can render straight from the solver's state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonGrid {
+  pub rows: Vec<JsonGridRow>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonGridRow {
+  pub columns: Vec<JsonSubGrid>
+}
+
+impl JsonGrid {
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("Error writing grid")
+  }
+
+  pub fn from_json(json: &str) -> Result<Self, SolverError> {
+    serde_json::from_str(json).map_err(|error| SolverError::InvalidJson(error.to_string()))
+  }
 }
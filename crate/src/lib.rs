@@ -4,16 +4,382 @@ pub mod utils;
 pub mod cell;
 pub mod sub_grid;
 pub mod grid;
+pub mod error;
+pub mod generator;
+pub mod rating;
+pub mod format;
+pub mod constraint;
+pub mod multi_grid;
 
-use grid::grid::Grid;
+mod lib_test;
 
+use grid::{grid::{Grid, GridOf, LargeGrid}, JsonGrid, Position, Variant, Rule, Edge, EdgeClue, Technique};
+use error::SolverError;
+use generator::Symmetry;
+use cell::SetMethod;
+use utils::bit_utils::{OptionBits, option_words};
+
+// Options in the bits of a u64 for grids of up to 64 symbols (8 x 8), or a u128 for larger grids e.g. 10 x 10
+enum Grids {
+  Standard(Grid),
+  Large(LargeGrid)
+}
+
+// Body run with the grid, whichever option bits it has
+macro_rules! with_grid {
+  ($grids:expr, $grid:ident => $body:expr) => {
+    match $grids {
+      Grids::Standard($grid) => $body,
+      Grids::Large($grid) => $body
+    }
+  };
+}
+
+// Function called for the option bits of a grid of the columns and rows
+macro_rules! with_option_bits {
+  ($columns:expr, $rows:expr, $function:ident($($argument:expr),*)) => {
+    match large($columns, $rows) {
+      false => $function::<u64>($($argument),*),
+      true => $function::<u128>($($argument),*)
+    }
+  };
+}
+
+// More symbols than the bits of a u64
+fn large(columns: usize, rows: usize) -> bool {
+  columns * rows > u64::BITS as usize
+}
+
+// Live grid kept in WebAssembly memory, changed by each user action instead of being rebuilt and solved from scratch
+#[wasm_bindgen(js_name = Grid)]
+pub struct GridHandle {
+  grid: Grids
+}
+
+#[wasm_bindgen(js_class = Grid)]
+impl GridHandle {
+  #[wasm_bindgen(constructor)]
+  pub fn new(columns: usize, rows: usize) -> Result<GridHandle, JsError> {
+    let grid = match large(columns, rows) {
+      false => Grids::Standard(Grid::try_new(columns, rows)?),
+      true => Grids::Large(LargeGrid::try_new(columns, rows)?)
+    };
+
+    Ok(Self { grid })
+  }
+
+  pub fn set_by_symbol(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    symbol: char,
+    set_method: SetMethod
+  ) -> Result<(), JsError> {
+    with_grid!(&mut self.grid, grid => {
+      Ok(grid.try_set_by_symbol(sub_grid_column, sub_grid_row, cell_column, cell_row, symbol, set_method)?)
+    })
+  }
+
+  pub fn fix_by_position(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option_column: usize,
+    option_row: usize
+  ) -> Result<(), JsError> {
+    with_grid!(&mut self.grid, grid => {
+      Ok(grid.try_fix_by_position(sub_grid_column, sub_grid_row, cell_column, cell_row, option_column, option_row)?)
+    })
+  }
+
+  // True if only the last option is left i.e. the cell is solved - the option a single bit, within the lowest bits for
+  //   grids with more symbols than the bits of a number
+  pub fn remove_option(
+    &mut self,
+    sub_grid_column: usize,
+    sub_grid_row: usize,
+    cell_column: usize,
+    cell_row: usize,
+    option: usize
+  ) -> Result<bool, JsError> {
+    with_grid!(&mut self.grid, grid => {
+      Ok(grid.try_remove_option(sub_grid_column, sub_grid_row, cell_column, cell_row, OptionBits::from_usize(option))?)
+    })
+  }
+
+  pub fn unfix(&mut self, sub_grid_column: usize, sub_grid_row: usize, cell_column: usize, cell_row: usize) -> Result<(), JsError> {
+    let position = Position { sub_grid_column, sub_grid_row, cell_column, cell_row };
+    with_grid!(&mut self.grid, grid => {
+      if !grid.valid_position(&position) {
+        return Err(SolverError::<u64>::InvalidPosition(position).into());
+      }
+
+      grid.unfix(sub_grid_column, sub_grid_row, cell_column, cell_row);
+    });
+    Ok(())
+  }
+
+  pub fn reset(&mut self) {
+    with_grid!(&mut self.grid, grid => grid.reset());
+  }
+
+  // Extra houses e.g. the diagonals, kept when reset - set before any cells
+  pub fn set_variant(&mut self, variant: Variant) {
+    with_grid!(&mut self.grid, grid => grid.set_variant(variant));
+  }
+
+  // Rule between cells a move apart e.g. anti-knight, kept when reset - added before any cells
+  pub fn add_rule(&mut self, rule: Rule) {
+    with_grid!(&mut self.grid, grid => grid.add_rule(rule));
+  }
+
+  // Edge clues as JSON e.g. [{"clue":"whiteDot","first":{...},"second":{...}}], kept when reset - added before any cells
+  pub fn add_edges(&mut self, json: &str) -> Result<(), JsError> {
+    let edges: Vec<Edge> = serde_json::from_str(json).map_err(|error| SolverError::<u64>::InvalidJson(error.to_string()))?;
+    for edge in edges.into_iter() {
+      with_grid!(&mut self.grid, grid => grid.add_edge(edge)?);
+    }
+
+    Ok(())
+  }
+
+  // Adjacent cells without a clue of the kind don't match it e.g. no white dot means not consecutive
+  pub fn add_negative_clue(&mut self, clue: EdgeClue) {
+    with_grid!(&mut self.grid, grid => grid.add_negative_clue(clue));
+  }
+
+  // Jigsaw regions replacing the sub-grids, as a layout with the id of each cell's region e.g. "a a a b | ...", kept
+  //   when reset - set before any cells
+  pub fn set_regions(&mut self, layout: &str) -> Result<(), JsError> {
+    with_grid!(&mut self.grid, grid => {
+      let (columns, rows) = grid.dimensions();
+      Ok(grid.set_regions(format::format::parse_houses(columns, rows, layout)?)?)
+    })
+  }
+
+  // Extra houses e.g. Windoku windows, as a layout with the id of each cell's house ('.' for none), kept when reset - added
+  //   before any cells
+  pub fn add_houses(&mut self, layout: &str) -> Result<(), JsError> {
+    with_grid!(&mut self.grid, grid => {
+      let (columns, rows) = grid.dimensions();
+      for positions in format::format::parse_houses(columns, rows, layout)?.into_iter() {
+        grid.add_house(positions)?;
+      }
+    });
+
+    Ok(())
+  }
+
+  // Constraints as JSON e.g. [{"cage":{"sum":3,"positions":[...]}}], kept when reset
+  pub fn add_constraints(&mut self, json: &str) -> Result<(), JsError> {
+    for item in constraint::constraint::from_json(json)?.into_iter() {
+      with_grid!(&mut self.grid, grid => grid.add_constraint(item)?);
+    }
+
+    Ok(())
+  }
+
+  // Sandwich and little killer clues outside the grid e.g. "r3=12 c1=0 r1c2se=15", kept when reset
+  pub fn add_outside_clues(&mut self, text: &str) -> Result<(), JsError> {
+    with_grid!(&mut self.grid, grid => {
+      let (columns, rows) = grid.dimensions();
+      for item in format::format::parse_outside_clues(columns, rows, text)?.into_iter() {
+        grid.add_constraint(item)?;
+      }
+    });
+
+    Ok(())
+  }
+
+  pub fn solve(&mut self) -> Result<bool, JsError> {
+    with_grid!(&mut self.grid, grid => Ok(grid.try_solve()?))
+  }
+
+  pub fn solved(&self) -> bool {
+    with_grid!(&self.grid, grid => grid.solved())
+  }
+
+  // Options of every cell, as passed to to_json
+  pub fn to_options(&mut self) -> Vec<usize> {
+    with_grid!(&mut self.grid, grid => grid.to_options())
+  }
+
+  // Snapshot to render, as JSON
+  pub fn to_json(&self) -> String {
+    with_grid!(&self.grid, grid => grid.to_json().to_json())
+  }
+}
+
+// Options of each cell as numbers, for grids with more symbols than the bits of a number (e.g. 10 x 10 in 64 bits) a
+//   cell's options split across more than one number, lowest bits first
 #[wasm_bindgen]
-pub fn solve(columns: usize, rows: usize, input: Vec<usize>) -> Vec<usize> {
-  let mut grid = Grid::new(columns, rows);
+pub fn solve(columns: usize, rows: usize, input: Vec<usize>) -> Result<Vec<usize>, JsError> {
+  with_option_bits!(columns, rows, solve_of(columns, rows, &input))
+}
+
+fn solve_of<T: OptionBits>(columns: usize, rows: usize, input: &[usize]) -> Result<Vec<usize>, JsError> {
+  let mut grid = GridOf::<T>::try_new(columns, rows)?;
+
+  grid.try_load_set_options(&grid.from_options(input)?)?;
+  grid.try_solve()?;
+
+  Ok(grid.to_options())
+}
+
+// Every option set, struck out or removed while solving, as JSON
+#[wasm_bindgen]
+pub fn solve_trace(columns: usize, rows: usize, input: Vec<usize>) -> Result<String, JsError> {
+  with_option_bits!(columns, rows, solve_trace_of(columns, rows, &input))
+}
+
+fn solve_trace_of<T: OptionBits>(columns: usize, rows: usize, input: &[usize]) -> Result<String, JsError> {
+  let mut grid = GridOf::<T>::try_new(columns, rows)?;
+  grid.enable_trace();
+
+  grid.try_load_set_options(&grid.from_options(input)?)?;
+  grid.try_solve()?;
+
+  Ok(grid.take_trace().unwrap_or_default().to_json())
+}
+
+// Positions of the cells in the first contradiction found, flattened as
+//   [sub_grid_column, sub_grid_row, cell_column, cell_row, ...] - empty if consistent
+#[wasm_bindgen]
+pub fn check_consistency(columns: usize, rows: usize, input: Vec<usize>) -> Result<Vec<usize>, JsError> {
+  with_option_bits!(columns, rows, check_consistency_of(columns, rows, &input))
+}
+
+fn check_consistency_of<T: OptionBits>(columns: usize, rows: usize, input: &[usize]) -> Result<Vec<usize>, JsError> {
+  let mut grid = GridOf::<T>::try_new(columns, rows)?;
+
+  let options = grid.from_options(input)?;
+  grid.validate_set_options(&options)?;
+  grid.load_set_options(&options);
+
+  let positions = match grid.check_consistency() {
+    Some(contradiction) => contradiction.positions(),
+    None => Vec::new()
+  };
+
+  Ok(positions.iter()
+    .flat_map(|x| vec![x.sub_grid_column, x.sub_grid_row, x.cell_column, x.cell_row])
+    .collect())
+}
+
+// Number of solutions found, up to the limit, followed by the options of the first two distinct solutions found
+#[wasm_bindgen]
+pub fn count_solutions(columns: usize, rows: usize, input: Vec<usize>, limit: usize) -> Result<Vec<usize>, JsError> {
+  with_option_bits!(columns, rows, count_solutions_of(columns, rows, &input, limit))
+}
+
+fn count_solutions_of<T: OptionBits>(columns: usize, rows: usize, input: &[usize], limit: usize) -> Result<Vec<usize>, JsError> {
+  let mut grid = GridOf::<T>::try_new(columns, rows)?;
+
+  match grid.try_load_set_options(&grid.from_options(input)?) {
+    Err(SolverError::DuplicateGiven { .. }) | Err(SolverError::Contradiction(_)) => return Ok(vec![0]),  // No solutions
+    result => result?
+  }
+
+  let mut solutions = grid.count_solutions(limit);
+
+  let mut output = vec![solutions.total];
+  for solution in solutions.grids.iter_mut() {
+    output.extend(solution.to_options());
+  }
+
+  Ok(output)
+}
+
+// Set options of a new puzzle with a unique solution, as passed to solve - with its hardest technique the difficulty,
+//   if any
+#[wasm_bindgen]
+pub fn generate(
+  columns: usize,
+  rows: usize,
+  seed: u32,
+  symmetry: Symmetry,
+  difficulty: Option<Technique>
+) -> Result<Vec<usize>, JsError> {
+  with_option_bits!(columns, rows, generate_of(columns, rows, seed, symmetry, difficulty))
+}
+
+fn generate_of<T: OptionBits>(
+  columns: usize,
+  rows: usize,
+  seed: u32,
+  symmetry: Symmetry,
+  difficulty: Option<Technique>
+) -> Result<Vec<usize>, JsError> {
+  let puzzle = generator::generator::generate::<T>(columns, rows, seed as u64, symmetry, difficulty)?;
+
+  let words = option_words(columns * rows);
+  Ok(puzzle.givens.iter().flat_map(|x| x.to_words(words)).collect())
+}
+
+// New puzzle with its solution and rating, as JSON
+#[wasm_bindgen]
+pub fn generate_puzzle(
+  columns: usize,
+  rows: usize,
+  seed: u32,
+  symmetry: Symmetry,
+  difficulty: Option<Technique>
+) -> Result<String, JsError> {
+  with_option_bits!(columns, rows, generate_puzzle_of(columns, rows, seed, symmetry, difficulty))
+}
+
+fn generate_puzzle_of<T: OptionBits>(
+  columns: usize,
+  rows: usize,
+  seed: u32,
+  symmetry: Symmetry,
+  difficulty: Option<Technique>
+) -> Result<String, JsError> {
+  let puzzle = generator::generator::generate::<T>(columns, rows, seed as u64, symmetry, difficulty)?;
+
+  Ok(serde_json::to_string(&puzzle)?)
+}
+
+// Hardest technique, the number of times each technique was used and the score, as JSON
+#[wasm_bindgen]
+pub fn rate(columns: usize, rows: usize, input: Vec<usize>) -> Result<String, JsError> {
+  with_option_bits!(columns, rows, rate_of(columns, rows, &input))
+}
+
+fn rate_of<T: OptionBits>(columns: usize, rows: usize, input: &[usize]) -> Result<String, JsError> {
+  let options = GridOf::<T>::try_new(columns, rows)?.from_options(input)?;
+  let rating = rating::rating::rate_set_options(columns, rows, &options)?;
+
+  Ok(serde_json::to_string(&rating)?)
+}
+
+// Snapshot of the grid with the options of every cell (as returned by to_options), as JSON to render
+#[wasm_bindgen]
+pub fn to_json(columns: usize, rows: usize, input: Vec<usize>) -> Result<String, JsError> {
+  with_option_bits!(columns, rows, to_json_of(columns, rows, &input))
+}
+
+fn to_json_of<T: OptionBits>(columns: usize, rows: usize, input: &[usize]) -> Result<String, JsError> {
+  let mut grid = GridOf::<T>::try_new(columns, rows)?;
+
+  grid.try_load_options(&grid.from_options(input)?)?;
+
+  Ok(grid.to_json().to_json())
+}
+
+// Options of every cell of a JSON snapshot, as returned by to_options
+#[wasm_bindgen]
+pub fn from_json(columns: usize, rows: usize, json: &str) -> Result<Vec<usize>, JsError> {
+  with_option_bits!(columns, rows, from_json_of(columns, rows, json))
+}
 
-  let u64_input = input.iter().map(|&x| x as u64).collect::<Vec<u64>>();
-  grid.load_set_options(&u64_input);
-  grid.solve();
+fn from_json_of<T: OptionBits>(columns: usize, rows: usize, json: &str) -> Result<Vec<usize>, JsError> {
+  let mut grid = GridOf::<T>::try_new(columns, rows)?;
+  grid.set_json(&JsonGrid::from_json(json)?)?;
 
-  grid.to_options()
+  Ok(grid.to_options())
 }
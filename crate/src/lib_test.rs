@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod exports {
+  use crate::{GridHandle, check_consistency, to_json, from_json};
+  use crate::cell::SetMethod;
+
+  const CELLS: usize = 100 * 100;
+
+  #[test]
+  fn it_creates_a_10x10_grid() {
+    let mut handle = GridHandle::new(10, 10).unwrap();
+    handle.set_by_symbol(0, 0, 0, 0, 'Þ', SetMethod::User).unwrap();  // Last symbol, beyond the bits of a u64
+
+    let options = handle.to_options();
+    assert_eq!(options.len(), CELLS * 2);                           // 100 options in 2 numbers a cell
+    assert_eq!(options[..4], [0, 1 << 35, usize::MAX, (1 << 35) - 1]);
+    assert!(!handle.solved());
+  }
+
+  #[test]
+  fn it_checks_a_10x10_grid() {
+    let mut input = vec![0; CELLS * 2];
+    input[1] = 1 << 35;
+    assert!(check_consistency(10, 10, input.clone()).unwrap().is_empty());
+
+    input[3] = 1 << 35;                                             // Repeated in the next cell
+    assert_eq!(check_consistency(10, 10, input).unwrap().len(), 2 * 4);
+  }
+
+  #[test]
+  fn it_round_trips_a_10x10_grid_through_json() {
+    let mut options = vec![usize::MAX; CELLS * 2];
+    for index in (1..options.len()).step_by(2) {
+      options[index] = (1 << 36) - 1;
+    }
+    options[1] = 1 << 2;                                            // Option 67
+
+    assert_eq!(from_json(10, 10, &to_json(10, 10, options.clone()).unwrap()).unwrap(), options);
+  }
+}
@@ -31,6 +31,6 @@ let hard3x3 = vec![
     4, 0, 0, 8
   ];
 
-  let output = solve(3, 3, hard3x3);
+  let output = solve(3, 3, hard3x3).expect("Unable to solve puzzle");
   println!("OUTPUT: {:?}", output);
 }
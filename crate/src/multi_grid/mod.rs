@@ -0,0 +1,13 @@
+pub mod multi_grid;
+
+mod multi_grid_test;
+
+// Sub-grid shared by two of the grids, at the sub-grid column and row within each e.g. a Samurai's centre grid's top left
+//   sub-grid is the bottom right sub-grid of the top left grid
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SharedSubGrid {
+  pub first: usize,                                                 // Index of the grid
+  pub first_sub_grid: (usize, usize),
+  pub second: usize,
+  pub second_sub_grid: (usize, usize)
+}
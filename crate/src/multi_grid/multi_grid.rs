@@ -0,0 +1,228 @@
+use crate::cell::SetMethod;
+use crate::error::SolverError;
+use crate::grid::{grid::GridOf, Contradiction, House, Position};
+use crate::multi_grid::SharedSubGrid;
+use crate::utils::bit_utils::{OptionBits, number_of_bits_set};
+
+// Overlapping grids e.g. Samurai or other gattai layouts, with the shared sub-grids' cells kept the same in each grid
+pub type MultiGrid = MultiGridOf<u64>;
+
+#[derive(Debug, Clone, Default)]
+pub struct MultiGridOf<T: OptionBits> {
+  grids: Vec<GridOf<T>>,
+  shared: Vec<SharedSubGrid>
+}
+
+impl<T: OptionBits> MultiGridOf<T> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Five grids: the centre grid (0) sharing its corner sub-grids with the inner corner of the top left, top right,
+  //   bottom left and bottom right grids (1 to 4)
+  pub fn samurai(columns: usize, rows: usize) -> Result<Self, SolverError<T>> {
+    let mut multi_grid = Self::new();
+    for _ in 0..5 {
+      multi_grid.add_grid(GridOf::try_new(columns, rows)?);
+    }
+
+    let (last_column, last_row) = (columns - 1, rows - 1);          // Sub-grids across and down
+    let corners = [(0, 0), (last_column, 0), (0, last_row), (last_column, last_row)];
+    for (index, &(column, row)) in corners.iter().enumerate() {
+      multi_grid.share_sub_grid(SharedSubGrid {
+        first: 0,
+        first_sub_grid: (column, row),
+        second: index + 1,
+        second_sub_grid: (last_column - column, last_row - row)
+      })?;
+    }
+
+    Ok(multi_grid)
+  }
+
+  // Index of the grid added
+  pub fn add_grid(&mut self, grid: GridOf<T>) -> usize {
+    self.grids.push(grid);
+    self.grids.len() - 1
+  }
+
+  // Sub-grid of two different grids of the same dimensions, kept the same from then on - declared before any cells
+  pub fn share_sub_grid(&mut self, shared: SharedSubGrid) -> Result<(), SolverError<T>> {
+    let grids = [shared.first, shared.second];
+    if shared.first == shared.second || grids.iter().any(|&x| x >= self.grids.len()) {
+      return Err(SolverError::InvalidSharedSubGrid(format!("grids {} and {} of {}", shared.first, shared.second, self.grids.len())));
+    }
+
+    let (columns, rows) = self.grids[shared.first].dimensions();
+    if self.grids[shared.second].dimensions() != (columns, rows) {
+      return Err(SolverError::InvalidSharedSubGrid(format!("grids {} and {} of different sizes", shared.first, shared.second)));
+    }
+
+    for &(column, row) in [shared.first_sub_grid, shared.second_sub_grid].iter() {
+      if column >= columns || row >= rows {
+        return Err(SolverError::InvalidSharedSubGrid(format!("no sub-grid {}, {} in a {} x {} grid", column, row, columns, rows)));
+      }
+    }
+
+    self.shared.push(shared);
+    self.share_options()?;
+    Ok(())
+  }
+
+  pub fn grids(&self) -> &[GridOf<T>] {
+    &self.grids
+  }
+
+  pub fn grid(&self, index: usize) -> Option<&GridOf<T>> {
+    self.grids.get(index)
+  }
+
+  pub fn shared_sub_grids(&self) -> &[SharedSubGrid] {
+    &self.shared
+  }
+
+  // Givens of each grid, as loaded by Grid::load_set_options, then shared - a contradiction if a shared cell is left
+  //   with no option given in both grids
+  pub fn load_set_options(&mut self, options: &[Vec<T>]) -> Result<(), SolverError<T>> {
+    if options.len() != self.grids.len() {
+      return Err(SolverError::InvalidInputLength { expected: self.grids.len(), actual: options.len() });
+    }
+
+    for (grid, grid_options) in self.grids.iter_mut().zip(options.iter()) {
+      grid.try_load_set_options(grid_options)?;
+    }
+
+    self.share_options()?;
+    Ok(())
+  }
+
+  // Set in the grid, and in the other grids if the cell is shared
+  pub fn set_by_symbol(&mut self, grid: usize, position: Position, symbol: char, set_method: SetMethod) -> Result<(), SolverError<T>> {
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = position;
+    self.get_grid_mut(grid)?.try_set_by_symbol(sub_grid_column, sub_grid_row, cell_column, cell_row, symbol, set_method)?;
+    self.share_options()?;
+    Ok(())
+  }
+
+  // Removed from the grid, and from the other grids if the cell is shared - true if only the last option is left
+  pub fn remove_option(&mut self, grid: usize, position: Position, option: T) -> Result<bool, SolverError<T>> {
+    let Position { sub_grid_column, sub_grid_row, cell_column, cell_row } = position;
+    let solved = self.get_grid_mut(grid)?.try_remove_option(sub_grid_column, sub_grid_row, cell_column, cell_row, option)?;
+    self.share_options()?;
+    Ok(solved)
+  }
+
+  // Solve each grid in turn, sharing the options of the shared sub-grids after each, until no grid makes progress or a
+  //   shared cell is left with no options
+  pub fn solve(&mut self) -> bool {
+    let mut progress = true;
+    while progress {
+      progress = false;
+
+      for index in 0..self.grids.len() {
+        let before = options(&self.grids[index]);
+        self.grids[index].solve();
+        progress |= options(&self.grids[index]) != before;
+        match self.share_options() {
+          Ok(removed) => progress |= removed,
+          Err(_) => return false                                    // Left for is_valid
+        }
+      }
+    }
+
+    self.solved()
+  }
+
+  pub fn solved(&self) -> bool {
+    self.grids.iter().all(|x| x.solved())
+  }
+
+  // No contradiction in any grid, and the cells of each shared sub-grid with options in common
+  pub fn is_valid(&self) -> bool {
+    self.grids.iter().all(|x| x.is_valid()) && self.shared.iter().all(|shared| {
+      self.shared_positions(shared).iter().all(|(first, second)| {
+        self.grids[shared.first].get_cell(first).options & self.grids[shared.second].get_cell(second).options != T::ZERO
+      })
+    })
+  }
+
+  fn get_grid_mut(&mut self, grid: usize) -> Result<&mut GridOf<T>, SolverError<T>> {
+    let total = self.grids.len();
+    self.grids.get_mut(grid).ok_or(SolverError::InvalidGrid { index: grid, total })
+  }
+
+  // Positions of each cell of the shared sub-grid, in the first and the second grid
+  fn shared_positions(&self, shared: &SharedSubGrid) -> Vec<(Position, Position)> {
+    let (first_column, first_row) = shared.first_sub_grid;
+    let (second_column, second_row) = shared.second_sub_grid;
+    let first = self.grids[shared.first].get_house_positions(&House::SubGrid(first_column, first_row));
+    let second = self.grids[shared.second].get_house_positions(&House::SubGrid(second_column, second_row));
+
+    first.into_iter().zip(second).collect()
+  }
+
+  // Options of each shared cell limited to those in both grids, repeated until nothing changes as striking out in one
+  //   grid may change the other cells it shares - true if any option removed, a contradiction at the cell's position in
+  //   the first grid if none are in both
+  fn share_options(&mut self) -> Result<bool, SolverError<T>> {
+    let mut removed = false;
+
+    let mut changed = true;
+    while changed {
+      changed = false;
+
+      for shared in self.shared.clone().iter() {
+        for (first, second) in self.shared_positions(shared).iter() {
+          let first_cell = self.grids[shared.first].get_cell(first);
+          let second_cell = self.grids[shared.second].get_cell(second);
+          let options = first_cell.options & second_cell.options;
+          if options == T::ZERO {
+            return Err(SolverError::Contradiction(Contradiction::NoOptions(*first)));
+          }
+
+          let set_method = match (first_cell.solved(), second_cell.solved()) {
+            (true, _) => first_cell.set_method,                     // Given in one grid given in the other
+            (_, true) => second_cell.set_method,
+            _ => SetMethod::Calculated
+          };
+          changed |= limit_options(&mut self.grids[shared.first], first, options, set_method);
+          changed |= limit_options(&mut self.grids[shared.second], second, options, set_method);
+        }
+      }
+
+      removed |= changed;
+    }
+
+    Ok(removed)
+  }
+}
+
+// Options of every cell, row by row
+fn options<T: OptionBits>(grid: &GridOf<T>) -> Vec<T> {
+  let (columns, rows) = grid.dimensions();
+  let max_options = columns * rows;
+
+  (0..max_options * max_options).map(|index| grid.get_cell(&grid.position(index % max_options, index / max_options)).options).collect()
+}
+
+// Cell's options limited to the options, set (striking out) if only one is left - true if any option removed
+fn limit_options<T: OptionBits>(grid: &mut GridOf<T>, position: &Position, options: T, set_method: SetMethod) -> bool {
+  let cell = grid.get_cell(position);
+  let extra_options = cell.options & !options;
+  if extra_options == T::ZERO {
+    return false;
+  }
+
+  if number_of_bits_set(options) == 1 {
+    grid.set_by_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, options, set_method);
+  } else {
+    let mut remaining = extra_options;
+    while remaining != T::ZERO {
+      let option = T::bit(remaining.trailing_zeros());              // lowest set bit value
+      remaining -= option;
+      grid.remove_option(position.sub_grid_column, position.sub_grid_row, position.cell_column, position.cell_row, option);
+    }
+  }
+
+  true
+}
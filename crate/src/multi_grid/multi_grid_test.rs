@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod multi_grid {
+  use crate::cell::SetMethod;
+  use crate::error::SolverError;
+  use crate::format::format;
+  use crate::grid::{grid::Grid, Contradiction};
+  use crate::multi_grid::{multi_grid::{MultiGrid, MultiGridOf}, SharedSubGrid};
+
+  // Two 4 x 4 grids side by side, the right column of sub-grids of the first being the left column of the second
+  fn pair() -> MultiGrid {
+    let mut multi_grid = MultiGrid::new();
+    multi_grid.add_grid(Grid::new(2, 2));
+    multi_grid.add_grid(Grid::new(2, 2));
+    for row in 0..2 {
+      multi_grid.share_sub_grid(SharedSubGrid { first: 0, first_sub_grid: (1, row), second: 1, second_sub_grid: (0, row) }).unwrap();
+    }
+
+    multi_grid
+  }
+
+  #[test]
+  fn it_shares_the_corners_of_a_samurai() {
+    let multi_grid = MultiGrid::samurai(3, 3).unwrap();
+
+    assert_eq!(multi_grid.grids().len(), 5);
+    assert_eq!(multi_grid.shared_sub_grids()[0], SharedSubGrid { first: 0, first_sub_grid: (0, 0), second: 1, second_sub_grid: (2, 2) });
+    assert_eq!(multi_grid.shared_sub_grids()[3], SharedSubGrid { first: 0, first_sub_grid: (2, 2), second: 4, second_sub_grid: (0, 0) });
+  }
+
+  #[test]
+  fn it_sets_a_shared_cell_in_both_grids() {
+    let mut multi_grid = pair();
+    let position = multi_grid.grid(0).unwrap().position(2, 0);
+    multi_grid.set_by_symbol(0, position, '3', SetMethod::Loaded).unwrap();
+
+    let second = multi_grid.grid(1).unwrap();
+    let cell = second.get_cell(&second.position(0, 0));
+    assert_eq!((cell.options, cell.set_method), (4, SetMethod::Loaded));
+    assert!(!second.get_cell(&second.position(3, 0)).contains_option(4));  // Struck out in the second grid's row
+  }
+
+  #[test]
+  fn it_removes_options_from_both_grids() {
+    let mut multi_grid = pair();
+    let position = multi_grid.grid(1).unwrap().position(1, 3);
+    assert!(!multi_grid.remove_option(1, position, 2).unwrap());
+
+    let first = multi_grid.grid(0).unwrap();
+    assert_eq!(first.get_cell(&first.position(3, 3)).options, 1 + 4 + 8);
+    assert!(multi_grid.is_valid());
+  }
+
+  #[test]
+  fn it_rejects_invalid_shared_sub_grids() {
+    let mut multi_grid = pair();
+    multi_grid.add_grid(Grid::new(3, 2));
+
+    let invalid = [
+      SharedSubGrid { first: 0, first_sub_grid: (0, 0), second: 0, second_sub_grid: (1, 1) },  // Same grid
+      SharedSubGrid { first: 0, first_sub_grid: (0, 0), second: 3, second_sub_grid: (0, 0) },
+      SharedSubGrid { first: 0, first_sub_grid: (0, 0), second: 2, second_sub_grid: (0, 0) },  // Different sizes
+      SharedSubGrid { first: 0, first_sub_grid: (2, 0), second: 1, second_sub_grid: (0, 0) }
+    ];
+    for &shared in invalid.iter() {
+      assert!(matches!(multi_grid.share_sub_grid(shared), Err(SolverError::InvalidSharedSubGrid(_))));
+    }
+    assert_eq!(multi_grid.shared_sub_grids().len(), 2);
+    let position = multi_grid.grid(0).unwrap().position(0, 0);
+    assert_eq!(multi_grid.set_by_symbol(3, position, '1', SetMethod::User), Err(SolverError::InvalidGrid { index: 3, total: 3 }));
+  }
+
+  #[test]
+  fn it_rejects_shared_cells_given_different_symbols() {
+    let mut multi_grid = pair();
+    let givens = vec![format::parse(2, 2, "..1.............").unwrap(), format::parse(2, 2, "2...............").unwrap()];
+
+    let position = multi_grid.grid(0).unwrap().position(2, 0);
+    assert_eq!(multi_grid.load_set_options(&givens), Err(SolverError::Contradiction(Contradiction::NoOptions(position))));
+    assert!(!multi_grid.is_valid());
+  }
+
+  #[test]
+  fn it_solves_a_samurai() {
+    let puzzles = ["................", "......43.3...1..", "......12......4.", ".........1...3.2", "......34.2...4.1"];
+    let solutions = ["1234341221434321", "3421124343122134", "2134431234211243", "3421124321344312", "4312213412433421"];
+    let givens: Vec<Vec<u64>> = puzzles.iter().map(|x| format::parse(2, 2, x).unwrap()).collect();
+
+    let mut grid = Grid::new(2, 2);
+    grid.load_set_options(&givens[1]);
+    assert_eq!(grid.count_solutions(2).total, 2);                   // Only solved by the other grids
+
+    let mut multi_grid = MultiGrid::samurai(2, 2).unwrap();
+    multi_grid.load_set_options(&givens).unwrap();
+    assert!(multi_grid.solve());
+    for (grid, &solution) in multi_grid.grids().iter().zip(solutions.iter()) {
+      assert_eq!(grid.to_line(), solution);
+    }
+    assert!(multi_grid.is_valid());
+
+    assert_eq!(multi_grid.load_set_options(&givens[1..]), Err(SolverError::InvalidInputLength { expected: 5, actual: 4 }));
+  }
+
+  #[test]
+  fn it_solves_with_the_options_of_small_grids() {
+    let puzzles = ["................", "......43.3...1..", "......12......4.", ".........1...3.2", "......34.2...4.1"];
+    let givens: Vec<Vec<u16>> = puzzles.iter().map(|x| format::parse_options(2, 2, x).unwrap()).collect();
+
+    let mut multi_grid: MultiGridOf<u16> = MultiGridOf::samurai(2, 2).unwrap();
+    multi_grid.load_set_options(&givens).unwrap();
+    assert!(multi_grid.solve());
+    assert_eq!(multi_grid.grid(0).unwrap().to_line(), "1234341221434321");
+  }
+}
@@ -0,0 +1,36 @@
+use serde::{Serialize, Deserialize};
+
+use crate::grid::Technique;
+
+pub mod rating;
+
+mod rating_test;
+
+pub const TECHNIQUES: [Technique; 6] = [                           // Simplest first
+  Technique::NakedSingle,
+  Technique::HiddenSingle,
+  Technique::NakedSubset,
+  Technique::HiddenSubset,
+  Technique::BoxLineReduction,
+  Technique::Trial
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechniqueCount {
+  pub technique: Technique,
+  pub count: usize
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+  pub hardest: Option<Technique>,                                   // None if every cell is given
+  pub counts: Vec<TechniqueCount>,                                  // Steps using each technique, simplest first
+  pub score: usize,
+  pub solved: bool                                                  // False if the techniques could not solve the puzzle
+}
+
+impl Rating {
+  pub fn count(&self, technique: Technique) -> usize {
+    self.counts.iter().find(|x| x.technique == technique).map_or(0, |x| x.count)
+  }
+}
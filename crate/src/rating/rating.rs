@@ -0,0 +1,42 @@
+use crate::error::SolverError;
+use crate::grid::{grid::GridOf, Technique};
+use crate::rating::{Rating, TechniqueCount, TECHNIQUES};
+use crate::utils::bit_utils::OptionBits;
+
+// Solve step by step from the givens, always using the simplest technique that makes progress, and total up the
+//   techniques used - each single and box/line counted, not cascaded into the step before
+pub fn rate<T: OptionBits>(grid: &GridOf<T>) -> Rating {
+  let mut grid = grid.single_step_clone();
+  let mut counts: Vec<TechniqueCount> = TECHNIQUES.iter().map(|&technique| TechniqueCount { technique, count: 0 }).collect();
+  let mut hardest = None;
+
+  while let Some(step) = grid.next_step() {
+    grid.apply_step(&step);
+
+    counts[TECHNIQUES.iter().position(|&x| x == step.technique).unwrap()].count += 1;
+    hardest = hardest.max(Some(step.technique));                    // None before any technique
+  }
+
+  let score = counts.iter().map(|x| weight(x.technique) * x.count).sum();
+
+  Rating { hardest, counts, score, solved: grid.solved() }
+}
+
+pub fn rate_set_options<T: OptionBits>(columns: usize, rows: usize, options: &Vec<T>) -> Result<Rating, SolverError<T>> {
+  let mut grid = GridOf::try_new(columns, rows)?;
+  grid.try_load_set_options(options)?;
+
+  Ok(rate(&grid))
+}
+
+// Score for each use of the technique
+pub fn weight(technique: Technique) -> usize {
+  match technique {
+    Technique::NakedSingle => 1,
+    Technique::HiddenSingle => 2,
+    Technique::NakedSubset => 5,
+    Technique::HiddenSubset => 8,
+    Technique::BoxLineReduction => 12,
+    Technique::Trial => 30
+  }
+}
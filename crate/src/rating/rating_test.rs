@@ -0,0 +1,148 @@
+#[cfg(test)]
+mod rating {
+  use crate::error::SolverError;
+  use crate::format::format::parse;
+  use crate::grid::Technique;
+  use crate::rating::rating::{rate_set_options, weight};
+
+  #[test]
+  fn it_rates_a_puzzle_needing_only_naked_singles() {
+    let rating = rate_set_options(3, 3, &parse(3, 3, concat!(
+      "53..7....",
+      "6..195...",
+      ".98....6.",
+      "8...6...3",
+      "4..8.3..1",
+      "7...2...6",
+      ".6....28.",
+      "...419..5",
+      "....8..79"
+    )).unwrap()).unwrap();
+
+    assert!(rating.solved);
+    assert_eq!(rating.hardest, Some(Technique::NakedSingle));     // Each counted, not struck out along with the givens
+    assert!(rating.score > 0);
+    assert_eq!(rating.score, rating.count(Technique::NakedSingle) * weight(Technique::NakedSingle));
+  }
+
+  #[test]
+  fn it_rates_a_full_grid_as_needing_no_technique() {
+    let rating = rate_set_options(2, 2, &parse(2, 2, "1234341221434321").unwrap()).unwrap();
+
+    assert!(rating.solved);
+    assert_eq!(rating.hardest, None);
+    assert_eq!(rating.score, 0);
+  }
+
+  #[test]
+  fn it_rates_a_puzzle_needing_hidden_singles() {
+    let rating = rate_set_options(3, 2, &parse(3, 2, concat!(
+      "....1.",
+      "2.....",
+      ".6...4",
+      "...4.2",
+      "..5...",
+      "5.1..3"
+    )).unwrap()).unwrap();
+
+    assert!(rating.solved);
+    assert_eq!(rating.hardest, Some(Technique::HiddenSingle));
+    assert!(rating.count(Technique::HiddenSingle) > 0);
+    assert_eq!(
+      rating.score,
+      rating.count(Technique::NakedSingle) * weight(Technique::NakedSingle) +
+        rating.count(Technique::HiddenSingle) * weight(Technique::HiddenSingle)
+    );
+  }
+
+  #[test]
+  fn it_rates_a_puzzle_needing_a_sub_grid_subset() {
+    let rating = rate_set_options(3, 3, &parse(3, 3, concat!(
+      "4.....8.5",
+      ".3.......",
+      "...7.....",
+      ".2.....6.",
+      "....8.4..",
+      "....1....",
+      "...6.3.7.",
+      "5..2.....",
+      "1.4......"
+    )).unwrap()).unwrap();
+
+    assert!(rating.solved);
+    assert_eq!(rating.hardest, Some(Technique::HiddenSubset));    // Only found within a sub-grid
+    assert!(rating.count(Technique::NakedSubset) > 0);
+    assert_eq!(rating.count(Technique::BoxLineReduction), 0);
+    assert_eq!(rating.count(Technique::Trial), 0);
+  }
+
+  #[test]
+  fn it_rates_a_puzzle_needing_box_line_reduction() {
+    let rating = rate_set_options(3, 3, &parse(3, 3, concat!(
+      ".7.86....",
+      ".9...5..3",
+      "........6",
+      "..67.381.",
+      "8...1..9.",
+      "..92.....",
+      "........4",
+      ".2.3.....",
+      ".3...7.69"
+    )).unwrap()).unwrap();
+
+    assert!(rating.solved);
+    assert_eq!(rating.hardest, Some(Technique::BoxLineReduction));
+    assert_eq!(rating.count(Technique::BoxLineReduction), 1);
+    assert_eq!(rating.count(Technique::Trial), 0);
+  }
+
+  #[test]
+  fn it_rates_a_puzzle_needing_trial_highest() {
+    let subset = rate_set_options(3, 3, &parse(3, 3, concat!(
+      "4.....8.5",
+      ".3.......",
+      "...7.....",
+      ".2.....6.",
+      "....8.4..",
+      "....1....",
+      "...6.3.7.",
+      "5..2.....",
+      "1.4......"
+    )).unwrap()).unwrap();
+
+    let trial = rate_set_options(3, 3, &parse(3, 3, concat!(
+      "8.....69.",
+      "....1....",
+      ".2.3....8",
+      "5..2....7",
+      "3..897...",
+      ".6.....3.",
+      "6.4.8.2..",
+      ".9.56....",
+      "........1"
+    )).unwrap()).unwrap();
+
+    assert_eq!(trial.hardest, Some(Technique::Trial));
+    assert!(trial.score > subset.score);
+  }
+
+  #[test]
+  fn it_cannot_solve_a_puzzle_with_more_than_one_solution() {
+    let rating = rate_set_options(2, 2, &parse(2, 2, concat!(
+      "1...",
+      ".2..",
+      "..3.",
+      "...4"
+    )).unwrap()).unwrap();
+
+    assert_eq!(rating.solved, false);
+  }
+
+  #[test]
+  fn it_rejects_an_invalid_puzzle() {
+    assert_eq!(
+      rate_set_options(2, 2, &vec![0u64; 15]),
+      Err(SolverError::InvalidInputLength { expected: 16, actual: 15 })
+    );
+  }
+}
@@ -1,29 +1,49 @@
+use serde::{Serialize, Deserialize};
+
+use crate::cell::JsonCell;
+use crate::utils::bit_utils::OptionBits;
+
 pub mod sub_grid;
 
 mod sub_grid_test;
 
+// Snapshot of a sub-grid's cells by row (IJsonSubGrid)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonSubGrid {
+  #[serde(default)]
+  pub column: usize,                                                // Coordinates within the grid
+  #[serde(default)]
+  pub row: usize,
+  pub rows: Vec<JsonSubGridRow>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonSubGridRow {
+  pub columns: Vec<JsonCell>
+}
+
 #[derive(Debug)]
-pub struct BitOption {
+pub struct BitOption<T: OptionBits = u64> {
   pub sub_grid_column: usize,
   pub sub_grid_row: usize,
   pub cell_column: usize,
   pub cell_row: usize,
-  pub bits: u64
+  pub bits: T
 }
 
-pub struct StruckOutCell {
-  pub last_option_found: Option<BitOption>,
-  pub remove_option_from_column: Option<BitOption>,
-  pub remove_option_from_row: Option<BitOption>
+pub struct StruckOutCell<T: OptionBits = u64> {
+  pub last_option_found: Option<BitOption<T>>,
+  pub remove_option_from_column: Option<BitOption<T>>,
+  pub remove_option_from_row: Option<BitOption<T>>
 }
 
-pub struct StruckOutCells {
-  pub last_options_found: Vec<BitOption>,
-  pub removed_options_from_column: Vec<BitOption>,
-  pub removed_options_from_row: Vec<BitOption>
+pub struct StruckOutCells<T: OptionBits = u64> {
+  pub last_options_found: Vec<BitOption<T>>,
+  pub removed_options_from_column: Vec<BitOption<T>>,
+  pub removed_options_from_row: Vec<BitOption<T>>
 }
 
-impl StruckOutCells {
+impl<T: OptionBits> StruckOutCells<T> {
   pub fn new() -> Self {
     Self {
       last_options_found: Vec::new(),
@@ -32,7 +52,7 @@ impl StruckOutCells {
     }
   }
 
-  pub fn add(&mut self, struck_out_cell: StruckOutCell) {
+  pub fn add(&mut self, struck_out_cell: StruckOutCell<T>) {
     match struck_out_cell.last_option_found {
       Some(option) => self.last_options_found.push(option),
       None => ()
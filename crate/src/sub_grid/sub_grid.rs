@@ -1,18 +1,23 @@
 use std::fmt::{self, Display};
-use crate::cell::{cell::Cell, SetMethod};
-use crate::sub_grid::{BitOption, StruckOutCell, StruckOutCells};
+use crate::cell::{cell::CellOf, SetMethod};
+use crate::sub_grid::{BitOption, StruckOutCell, StruckOutCells, JsonSubGrid, JsonSubGridRow};
+use crate::error::SolverError;
+use crate::utils::bit_utils::OptionBits;
+
+// Sub-grid of a grid with up to 64 symbols - see SubGridOf for the option bits of smaller or larger grids
+pub type SubGrid = SubGridOf<u64>;
 
 #[derive(Debug, Clone)]
-pub struct SubGrid {
+pub struct SubGridOf<T: OptionBits> {
   max_columns: usize,
   max_rows: usize,
 
   pub column: usize,
   pub row: usize,
-  pub cells: Vec<Vec<Cell>>                                         // use get(column, row) -> returns cells[row][column]
+  pub cells: Vec<Vec<CellOf<T>>>                                    // use get(column, row) -> returns cells[row][column]
 }
 
-impl Display for SubGrid {
+impl<T: OptionBits> Display for SubGridOf<T> {
   fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
     fn add_separator_line(output: &mut String, max_columns: usize, max_rows: usize) {
       // All left aligned padding '-' to ''
@@ -53,18 +58,18 @@ impl Display for SubGrid {
   }
 }
 
-impl SubGrid {
+impl<T: OptionBits> SubGridOf<T> {
   pub fn new(max_columns: usize, max_rows: usize, column: usize, row: usize) -> Self {
-    let mut cells: Vec<Vec<Cell>> = Vec::with_capacity(max_rows);
+    let mut cells: Vec<Vec<CellOf<T>>> = Vec::with_capacity(max_rows);
 
     for row in 0..max_rows {
       cells.push(Vec::with_capacity(max_columns));
       for column in 0..max_columns {
-        cells[row].push(Cell::new(max_rows, max_columns, column, row)); // max columns and rows swopped
+        cells[row].push(CellOf::new(max_rows, max_columns, column, row)); // max columns and rows swopped
       }
     }
   
-    SubGrid {
+    Self {
       max_columns,
       max_rows,
       column,
@@ -81,12 +86,38 @@ impl SubGrid {
     }
   }
 
-  pub fn get(&mut self, column: usize, row: usize) -> &mut Cell {
+  pub fn get(&mut self, column: usize, row: usize) -> &mut CellOf<T> {
     // grids called by [column, row] but accessed by [row][column] for efficiency
     &mut self.cells[row][column]
   }
 
-  pub fn available_options_row(&self) -> Vec<u64> {
+  pub fn to_json(&self) -> JsonSubGrid {
+    JsonSubGrid {
+      column: self.column,
+      row: self.row,
+      rows: self.cells.iter()
+        .map(|cells| JsonSubGridRow { columns: cells.iter().map(|cell| cell.to_json()).collect() })
+        .collect()
+    }
+  }
+
+  pub fn set_json(&mut self, json: &JsonSubGrid) -> Result<(), SolverError<T>> {
+    if json.rows.len() != self.max_rows || json.rows.iter().any(|x| x.columns.len() != self.max_columns) {
+      return Err(SolverError::InvalidJson(
+        format!("Sub-grid {}, {} expected {} x {} cells", self.column, self.row, self.max_columns, self.max_rows)
+      ));
+    }
+
+    for (cells, json_row) in self.cells.iter_mut().zip(json.rows.iter()) {
+      for (cell, json_cell) in cells.iter_mut().zip(json_row.columns.iter()) {
+        cell.set_json(json_cell)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn available_options_row(&self) -> Vec<T> {
     let mut options_row = Vec::with_capacity(self.max_columns * self.max_rows);
 
     for row in 0..self.max_rows {
@@ -119,7 +150,7 @@ impl SubGrid {
     &mut self,
     column: usize,
     row: usize,
-    option: u64,
+    option: T,
     set_method: SetMethod
   ) -> bool {
     let cell = &mut self.cells[row][column];
@@ -136,13 +167,13 @@ impl SubGrid {
     row: usize,
     symbol: char,
     set_method: SetMethod
-  ) -> u64 {
+  ) -> T {
     let cell = &mut self.cells[row][column];
     if cell.set_method == SetMethod::Unset {
       cell.set_by_symbol(symbol, set_method);
       return cell.options;
     }
-    0
+    T::ZERO
   }
 
   pub fn set_by_index(
@@ -151,16 +182,16 @@ impl SubGrid {
     row: usize,
     index: usize,
     set_method: SetMethod
-  ) -> u64 {
+  ) -> T {
     let cell = &mut self.cells[row][column];
     if cell.set_method == SetMethod::Unset {
       cell.set_by_index(index, set_method);
       return cell.options;
     }
-    0
+    T::ZERO
   }
 
-  pub fn compare(&self, items: &Vec<Vec<Cell>>) -> bool {
+  pub fn compare(&self, items: &Vec<Vec<CellOf<T>>>) -> bool {
     let mut equal = true;
     let mut row = self.max_rows;
     while equal && row > 0 {
@@ -175,7 +206,7 @@ impl SubGrid {
     equal
   }
 
-  pub fn compare_ref(&self, items: &Vec<Vec<&Cell>>) -> bool {
+  pub fn compare_ref(&self, items: &Vec<Vec<&CellOf<T>>>) -> bool {
     let mut equal = true;
     let mut row = self.max_rows;
     while equal && row > 0 {
@@ -225,7 +256,7 @@ impl SubGrid {
     solved
   }
 
-  pub fn get_available_options_matrix(&self) -> Vec<Vec<u64>> {
+  pub fn get_available_options_matrix(&self) -> Vec<Vec<T>> {
     let mut matrix = Vec::with_capacity(self.max_rows - 1);
 
     for row in 0..self.max_rows {
@@ -239,8 +270,8 @@ impl SubGrid {
     matrix
   }
 
-  pub fn get_cells_matrix(&self) -> Vec<Vec<&Cell>> {
-    let mut matrix: Vec<Vec<&Cell>> = Vec::with_capacity(self.max_rows);
+  pub fn get_cells_matrix(&self) -> Vec<Vec<&CellOf<T>>> {
+    let mut matrix: Vec<Vec<&CellOf<T>>> = Vec::with_capacity(self.max_rows);
 
     for row in 0..self.max_rows {
       matrix.push(Vec::with_capacity(self.max_columns));
@@ -253,7 +284,7 @@ impl SubGrid {
     matrix
   }
 
-  pub fn get_unset_cells(&self) -> Vec<Cell> {
+  pub fn get_unset_cells(&self) -> Vec<CellOf<T>> {
     let mut unset_cells = Vec::new();
 
     for row in 0..self.max_rows {
@@ -267,7 +298,7 @@ impl SubGrid {
     unset_cells
   }
 
-  pub fn unset_cells(&self, total_unset_options: usize) -> Vec<Cell> {
+  pub fn unset_cells(&self, total_unset_options: usize) -> Vec<CellOf<T>> {
     let cells = self.get_unset_cells();
     let mut unset = Vec::new();
 
@@ -280,7 +311,7 @@ impl SubGrid {
     unset
   }
 
-  pub fn unset_cells_options(&self, total_unset_options: usize) -> Vec<u64> {
+  pub fn unset_cells_options(&self, total_unset_options: usize) -> Vec<T> {
     let cells = self.get_unset_cells();
     let mut unset_options = Vec::new();
 
@@ -293,7 +324,7 @@ impl SubGrid {
     unset_options
   }
 
-  pub fn get_available_options(&self) -> Vec<u64> {
+  pub fn get_available_options(&self) -> Vec<T> {
     let mut array = Vec::with_capacity(self.max_columns * self.max_rows);
     
     for row in 0..self.max_rows {
@@ -310,8 +341,8 @@ impl SubGrid {
     &mut self,
     cell_column: usize,
     cell_row: usize,
-    option: u64
-  ) -> StruckOutCells {
+    option: T
+  ) -> StruckOutCells<T> {
     let mut struck_out_cells = StruckOutCells::new();
 
     let mut column;
@@ -348,10 +379,10 @@ impl SubGrid {
     struck_out_cells
   }
 
-  fn get_struck_out_cell(&mut self, column: usize, row: usize, option: u64) -> StruckOutCell {
-    let mut last_option_found: Option<BitOption> = None;
-		let mut remove_option_from_column: Option<BitOption> = None;
-    let mut remove_option_from_row: Option<BitOption> = None;
+  fn get_struck_out_cell(&mut self, column: usize, row: usize, option: T) -> StruckOutCell<T> {
+    let mut last_option_found: Option<BitOption<T>> = None;
+		let mut remove_option_from_column: Option<BitOption<T>> = None;
+    let mut remove_option_from_row: Option<BitOption<T>> = None;
     
     if self.cells[row][column].remove_option(option) {
       last_option_found = Some(BitOption {
@@ -398,7 +429,7 @@ impl SubGrid {
     !self.cells[cell_row][cell_column].contains_symbol(symbol)
   }
 
-  pub fn remove_options_from_column(&mut self, cell_column: usize, options: u64) -> Vec<BitOption> {
+  pub fn remove_options_from_column(&mut self, cell_column: usize, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     for row in 0..self.max_rows {
@@ -416,7 +447,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn remove_options_from_row(&mut self, cell_row: usize, options: u64) -> Vec<BitOption> {
+  pub fn remove_options_from_row(&mut self, cell_row: usize, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     for column in 0..self.max_columns {
@@ -434,7 +465,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn remove_options_except_from_column(&mut self, exclude_column: usize, options: u64) -> Vec<BitOption> {
+  pub fn remove_options_except_from_column(&mut self, exclude_column: usize, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     let mut row: usize;
@@ -476,7 +507,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn remove_options_except_from_row(&mut self, exclude_row: usize, options: u64) -> Vec<BitOption> {
+  pub fn remove_options_except_from_row(&mut self, exclude_row: usize, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     let mut column: usize;
@@ -518,7 +549,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn remove_if_extra_options_from_column(&mut self, column: usize, options: u64) -> Vec<BitOption> {
+  pub fn remove_if_extra_options_from_column(&mut self, column: usize, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     for row in 0..self.max_rows {
@@ -536,7 +567,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn remove_if_extra_options_from_row(&mut self, row: usize, options: u64) -> Vec<BitOption> {
+  pub fn remove_if_extra_options_from_row(&mut self, row: usize, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     for column in 0..self.max_columns {
@@ -554,7 +585,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn remove_if_extra_options(&mut self, options: u64) -> Vec<BitOption> {
+  pub fn remove_if_extra_options(&mut self, options: T) -> Vec<BitOption<T>> {
     let mut last_options = Vec::new();
 
     for row in 0..self.max_rows {
@@ -574,7 +605,7 @@ impl SubGrid {
     last_options
   }
 
-  pub fn option_exists_in_column(&self, column: usize, option: u64) -> bool {
+  pub fn option_exists_in_column(&self, column: usize, option: T) -> bool {
     let mut found = false;
     let mut row = self.max_rows;
     while !found && row > 0 {
@@ -585,7 +616,7 @@ impl SubGrid {
     found
   }
 
-  pub fn option_exists_in_row(&self, row: usize, option: u64) -> bool {
+  pub fn option_exists_in_row(&self, row: usize, option: T) -> bool {
     let mut found = false;
     let mut column = self.max_columns;
     while !found && column > 0 {
@@ -596,43 +627,43 @@ impl SubGrid {
     found
   }
 
-  pub fn option_removed_from_column(&self, cell_column: usize, cell_row: usize, option: u64) -> bool {
+  pub fn option_removed_from_column(&self, cell_column: usize, cell_row: usize, option: T) -> bool {
     // Check if option removed from column
     let mut option_found = false;
     let mut row = self.max_rows;
     while !option_found && row > cell_row + 1 {
       row -= 1;
-      option_found = (self.cells[row][cell_column].options & option) > 0;
+      option_found = (self.cells[row][cell_column].options & option) != T::ZERO;
     }
 
     row -= 1;                                                       // Skip row_column
     while !option_found && row > 0 {
       row -= 1;
-      option_found = (self.cells[row][cell_column].options & option) > 0;
+      option_found = (self.cells[row][cell_column].options & option) != T::ZERO;
     }
   
     !option_found                                                   // If option not found then it was removed from self sub grid's column
   }
 
-  pub fn option_removed_from_row(&self, cell_column: usize, cell_row: usize, removed_option: u64) -> bool {
+  pub fn option_removed_from_row(&self, cell_column: usize, cell_row: usize, removed_option: T) -> bool {
     // Check if option removed from row
     let mut option_found = false;
     let mut column = self.max_columns;
     while !option_found && column > cell_column + 1 {
       column -= 1;
-      option_found = (self.cells[cell_row][column].options & removed_option) > 0;
+      option_found = (self.cells[cell_row][column].options & removed_option) != T::ZERO;
     }
     
     column -= 1;                                                    // Skip cell_column
     while !option_found && column > 0 {
       column -= 1;
-      option_found = (self.cells[cell_row][column].options & removed_option) > 0;
+      option_found = (self.cells[cell_row][column].options & removed_option) != T::ZERO;
     }
 
     !option_found                                                   // If option not found then it was removed from self sub grid's row
   }
 
-  pub fn set_cells(&self, _sub_grid: Vec<Vec<Cell>>) {
+  pub fn set_cells(&self, _sub_grid: Vec<Vec<CellOf<T>>>) {
     for _row in 0..self.max_rows {
       for _column in 0..self.max_columns {
         // self.cells[row][column] = Cell::new(sub_grid[row][column]); -> set using copy letructor ?
@@ -6,10 +6,10 @@
 // ]                                  [ 7,  8,  9],
 //                                    [10, 11, 12]
 //                                  ]
-pub fn square_rows(rows: &[Vec<u64>]) -> Vec<&[u64]> {
+pub fn square_rows<T: Copy>(rows: &[Vec<T>]) -> Vec<&[T]> {
   let total_rows = rows.len();
   let segments = if total_rows > 0 { rows[0].len() / total_rows } else { 0 };
-  let mut split_rows: Vec<&[u64]> = Vec::with_capacity(segments * segments);
+  let mut split_rows: Vec<&[T]> = Vec::with_capacity(segments * segments);
 
   for row in rows.iter() {
     let mut offset = 0;
@@ -30,7 +30,7 @@ pub fn square_rows(rows: &[Vec<u64>]) -> Vec<&[u64]> {
 //   [ 7,  8,  9],                  ]
 //   [10, 11, 12]
 // ]
-pub fn combine_rows(rows: &Vec<&[u64]>) -> Vec<Vec<u64>> {
+pub fn combine_rows<T: Copy>(rows: &Vec<&[T]>) -> Vec<Vec<T>> {
   let root = (rows.len() as f64).sqrt() as usize;
   let mut result = Vec::with_capacity(root);
 
@@ -55,10 +55,10 @@ pub fn combine_rows(rows: &Vec<&[u64]>) -> Vec<Vec<u64>> {
 //   [ 9, 10, 11, 12]             [ 9, 10, 13, 14],
 //   [13, 14, 15, 16]             [11, 12, 15, 16]
 // ]                            ]
-pub fn transpose_rows(columns: usize, rows: &Vec<Vec<u64>>) -> Vec<Vec<u64>> {
+pub fn transpose_rows<T: Copy>(columns: usize, rows: &Vec<Vec<T>>) -> Vec<Vec<T>> {
   let segments = rows.len() / columns;
   let size = rows.len() / segments;
-  let mut transposed: Vec<Vec<u64>> = Vec::with_capacity(rows.len());
+  let mut transposed: Vec<Vec<T>> = Vec::with_capacity(rows.len());
 
   for index in 0..segments {
     let from = index * size;
@@ -76,13 +76,13 @@ pub fn transpose_rows(columns: usize, rows: &Vec<Vec<u64>>) -> Vec<Vec<u64>> {
 //   31, 32, 33, 34,                  [31, 32, 33, 34],
 //   41, 42, 43, 44,                  [41, 42, 43, 44],
 // ]                                ]
-pub fn group_by_root(row: &Vec<u64>) -> Vec<Vec<u64>> {
+pub fn group_by_root<T: Copy>(row: &Vec<T>) -> Vec<Vec<T>> {
   let size = row.len();
   let root = (size as f64).sqrt() as usize;
-  let mut groups: Vec<Vec<u64>> = Vec::with_capacity(root);
+  let mut groups: Vec<Vec<T>> = Vec::with_capacity(root);
 
   for index in (0..size).step_by(root) {
-    let mut group: Vec<u64> = Vec::with_capacity(root);
+    let mut group: Vec<T> = Vec::with_capacity(root);
 
     group.extend(&row[index..index + root]);
     groups.push(group);
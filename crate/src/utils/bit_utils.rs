@@ -1,19 +1,99 @@
-// Population count
-pub fn number_of_bits_set(bits: u64) -> usize {
-  let mut count = 0;
-  let mut remaining_bits = bits;
-  while remaining_bits > 0 {
-    remaining_bits &= remaining_bits - 1;
-    count += 1;
+use std::fmt::{Binary, Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Sub, BitAnd, BitOr, BitXor, Not, Shl, Shr};
+use std::ops::{SubAssign, BitAndAssign, BitOrAssign, BitXorAssign, ShlAssign, ShrAssign};
+use serde::{Serialize, de::DeserializeOwned};
+
+// Unsigned integer holding a cell's options, a bit per symbol: u16 for up to 16 symbols e.g. 9 x 9 grids, u64 for up to
+//   64 e.g. 8 x 8 grids, or u128 for up to 128 e.g. 10 x 10 grids
+pub trait OptionBits:
+  Copy + Default + Debug + Display + Binary + Eq + Ord + Hash + Send + Sync + 'static +
+  Add<Output = Self> + Sub<Output = Self> + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self> +
+  Not<Output = Self> + Shl<usize, Output = Self> + Shr<usize, Output = Self> +
+  SubAssign + BitAndAssign + BitOrAssign + BitXorAssign + ShlAssign<usize> + ShrAssign<usize> +
+  Serialize + DeserializeOwned
+{
+  const BITS: usize;
+  const ZERO: Self;
+  const ONE: Self;
+
+  fn count_ones(self) -> usize;
+  fn trailing_zeros(self) -> usize;
+  fn leading_zeros(self) -> usize;
+  fn from_usize(value: usize) -> Self;
+  fn to_usize(self) -> usize;                                       // Lowest bits that fit
+
+  // Option at the index i.e. only that bit set
+  fn bit(index: usize) -> Self {
+    Self::ONE << index
+  }
+
+  // Lowest count bits set e.g. every option of a cell with count symbols - without overflowing when all bits are used
+  fn mask(count: usize) -> Self {
+    match count {
+      _ if count >= Self::BITS => !Self::ZERO,
+      _ => (Self::ONE << count) - Self::ONE
+    }
+  }
+
+  // Options as numbers e.g. for JavaScript, lowest bits first
+  fn to_words(self, words: usize) -> Vec<usize> {
+    (0..words).map(|word| (self >> (word * usize::BITS as usize)).to_usize()).collect()
   }
 
-  count
+  fn from_words(words: &[usize]) -> Self {
+    words.iter().enumerate().fold(Self::ZERO, |options, (word, &x)| options | Self::from_usize(x) << (word * usize::BITS as usize))
+  }
+}
+
+macro_rules! option_bits {
+  ($($bits:ty),*) => {
+    $(
+      impl OptionBits for $bits {
+        const BITS: usize = <$bits>::BITS as usize;
+        const ZERO: Self = 0;
+        const ONE: Self = 1;
+
+        fn count_ones(self) -> usize {
+          <$bits>::count_ones(self) as usize
+        }
+
+        fn trailing_zeros(self) -> usize {
+          <$bits>::trailing_zeros(self) as usize
+        }
+
+        fn leading_zeros(self) -> usize {
+          <$bits>::leading_zeros(self) as usize
+        }
+
+        fn from_usize(value: usize) -> Self {
+          value as $bits
+        }
+
+        fn to_usize(self) -> usize {
+          self as usize
+        }
+      }
+    )*
+  };
+}
+
+option_bits!(u16, u32, u64, u128);
+
+// Numbers needed for the options of a cell of a grid with count symbols, one unless more than the bits of a number
+pub fn option_words(count: usize) -> usize {
+  count.div_ceil(usize::BITS as usize)
+}
+
+// Population count
+pub fn number_of_bits_set<T: OptionBits>(bits: T) -> usize {
+  bits.count_ones()
 }
 
 // Return bits set within all passed elements (not XOR)
-pub fn bitwise_or(elements: &[u64]) -> u64 {
-  let mut total_ored = 0;
-  for element in elements.iter() {
+pub fn bitwise_or<T: OptionBits>(elements: &[T]) -> T {
+  let mut total_ored = T::ZERO;
+  for &element in elements.iter() {
     total_ored |= element;
   }
 
@@ -21,66 +101,39 @@ pub fn bitwise_or(elements: &[u64]) -> u64 {
 }
 
 // XOR all the values passed in to find an only option
-pub fn only_option(options: &[u64]) -> (bool, u64) {
-  let mut option = 0;
-  let mut filled = 0;
-  for element in options.iter() {
-    if element & element - 1 > 0 {                                  // Not a single base of 2 number (1, 2, 4, 8, ...)
+pub fn only_option<T: OptionBits>(options: &[T]) -> (bool, T) {
+  let mut option = T::ZERO;
+  let mut filled = T::ZERO;
+  for &element in options.iter() {
+    if element != T::ZERO && element & (element - T::ONE) > T::ZERO {  // Not a single base of 2 number (1, 2, 4, 8, ...)
       filled |= option & element;
       option ^= element;                                            // XOR
     }
   }
 
   option &= !filled;
-  (option > 0 && (option & option - 1) == 0, option)                // Single base of 2 number, but not 0
+  (option.count_ones() == 1, option)                                // Single base of 2 number, but not 0
 }
 
 // Index of first item in array containing bit
-pub fn containing_bit_index(array: &[u64], bit: u64) -> usize {
+pub fn containing_bit_index<T: OptionBits>(array: &[T], bit: T) -> usize {
   let mut index = 0;
-  while index < array.len() && (array[index] & bit) == 0 {
+  while index < array.len() && (array[index] & bit) == T::ZERO {
     index += 1;
   }
 
   index
 }
 
-pub fn highest_bit_position(v: u64) -> usize {
-  let multiply_de_bruijn_bit_position = [
-    0, 9, 1, 10, 13, 21, 2, 29, 11, 14, 16, 18, 22, 25, 3, 30,
-    8, 12, 20, 28, 15, 17, 24, 7, 19, 27, 23, 6, 26, 5, 4, 31];
-
-  fn highest_bit_index(mut n: u64) -> usize {
-    let mut bit = 0b1;
-    let mut next = n | n >> 1;
-    while next != n {
-      n = next;
-      bit <<= 1;
-      next = n | n >> bit;
-    }
-
-    ((n * 0x07C4ACDD >> 27) % 32) as usize
+// Index of the highest bit set, 0 if none
+pub fn highest_bit_position<T: OptionBits>(v: T) -> usize {
+  match v {
+    _ if v == T::ZERO => 0,
+    _ => T::BITS - 1 - v.leading_zeros()
   }
-
-  if v > 0xFFFFFFFF {                                               // more than 32 bits -> calc top half
-    return 32 + multiply_de_bruijn_bit_position[highest_bit_index(v >> 32)];
-  }
-
-  multiply_de_bruijn_bit_position[highest_bit_index(v)]
 }
 
-pub fn power_of_2_bit_positions(bit: u64) -> usize {
-  let mut remaining_bit = bit;
-  let mut index = 0;
-  while remaining_bit > 1 {
-    remaining_bit >>= 1;
-    index += 1;
-  }
-
-  index
+// Index of the bit of an option i.e. a single bit set
+pub fn power_of_2_bit_positions<T: OptionBits>(bit: T) -> usize {
+  highest_bit_position(bit)
 }
-
-// export const power_of_2_bit_positions: IHashMapOfPowerOf2 = {
-//     1: 0, 2: 1, 4: 2, 8: 3, 16: 4, 32: 5, 64: 6, 128: 7, 256: 8, 512: 9, 1024: 10, 2048: 11, 4096: 12, 8192: 13, 16384: 14, 32768: 15, 65536: 16, 131072: 17, 262144: 18,
-//     524288: 19, 1048576: 20, 2097152: 21, 4194304: 22, 8388608: 23, 16777216: 24, 33554432: 25, 67108864: 26, 134217728: 27, 268435456: 28, 536870912: 29, 1073741824: 30, 2147483648: 31
-// }
@@ -1,65 +1,58 @@
 #[derive(Debug, Clone)]
 pub struct Combinations {                                           // C(n, r) = n! / r!(n-r)!
-  set_bits_lookup_table: Vec<Vec<usize>>
+  max_items_select_from: usize
 }
 
 impl Combinations {
   pub fn new(max_items_select_from: usize) -> Self {
-    let set_bits = create_set_bits_lookup(max_items_select_from);
-    let mut set_bits_lookup_table = Vec::with_capacity(max_items_select_from);
-
-    let set_bits_length = set_bits.len();
-    for index in 0..=max_items_select_from {
-      let mut lookup_row = Vec::with_capacity(set_bits_length - 1);
-      for bit in 0..set_bits_length {                               // Get indices of items with respective choices
-        if set_bits[bit] == index {
-          lookup_row.push(bit);
-        }
-      }
-      set_bits_lookup_table.push(lookup_row);
-    }
-
-    Self { set_bits_lookup_table }
+    Self { max_items_select_from }
   }
 
   pub fn select<'a, T>(&self, from: &'a Vec<T>, pick: usize) -> Vec<Vec<&'a T>> {
-    // Get bit flags used to select the combinations from the lookup table, up to the number of items to select from
-    let set_bits = 1 << from.len();
-    let lookup_table = &self.set_bits_lookup_table[pick];
-    let mut combinations = Vec::with_capacity(lookup_table.len());
+    // Bit flags used to select the combinations, up to the number of items to select from
+    let total_items = from.len().min(self.max_items_select_from);
+    let mut combinations = Vec::new();
 
-    for index in 0..lookup_table.len() {
-      if lookup_table[index] < set_bits {
-        combinations.push(select_elements(&from, lookup_table[index]));
+    if pick == 0 {
+      combinations.push(Vec::new());
+    } else if pick <= total_items {
+      let set_bits: u128 = 1 << total_items;                        // Not usize, only 32 bits on wasm32
+      let mut select: u128 = (1 << pick) - 1;                       // Lowest bit flags with pick bits set
+      while select < set_bits {
+        combinations.push(select_elements(from, select));
+        select = next_with_same_bits_set(select);
       }
     }
 
     combinations
   }
-}
 
-// Populate array with the number of bits set i.e. [0] => 0, [1] => 1, [2] => 1, [3] => 2, ..., [333] => 5 (i.e. 101001101 has 5 bits set)
-fn create_set_bits_lookup(n: usize) -> Vec<usize> {
-  fn next_values(x: usize) -> [usize; 4] {
-    [x, x + 1, x + 1, x + 2]
-  }
+  // Number of combinations select would return i.e. C(n, r), without selecting them
+  pub fn count(&self, from: usize, pick: usize) -> u128 {
+    let total_items = from.min(self.max_items_select_from);
+    if pick > total_items {
+      return 0;
+    }
 
-  let mut lookup_table: Vec<usize> = Vec::new();
-  lookup_table.extend_from_slice(&next_values(0));                  // Starting values { 0, 1, 1, 2 }
-  let mut table_size = 4;
-  for _ in 2..n {
-    let offset = table_size >> 2;
-    for j in 0..(table_size >> 1) - offset {    
-      lookup_table.extend_from_slice(&next_values(lookup_table[j + offset]));
+    let mut count: u128 = 1;
+    for index in 0..pick {
+      count = count * (total_items - index) as u128 / (index + 1) as u128;  // Whole number after each division
     }
-    table_size <<= 1;
+
+    count
   }
+}
+
+// Next higher number with the same number of bits set (Gosper's hack) i.e. 0011 -> 0101 -> 0110 -> 1001 -> ...
+fn next_with_same_bits_set(bits: u128) -> u128 {
+  let lowest_bit = bits & bits.wrapping_neg();
+  let ripple = bits + lowest_bit;
 
-  lookup_table
+  (((ripple ^ bits) >> 2) / lowest_bit) | ripple
 }
 
 // Return elements where the index is in the select bit flag
-fn select_elements<'a, T>(from: &'a Vec<T>, select: usize) -> Vec<&'a T> {
+fn select_elements<T>(from: &[T], select: u128) -> Vec<&T> {
   let mut elements = Vec::with_capacity(from.len());
   for index in 0..from.len() {
     if (1 << index) & select > 0 {
@@ -69,3 +69,53 @@ mod select {
     assert_eq!(expected, actual);
   }
 }
+
+#[cfg(test)]
+mod select_large {
+  use crate::utils::combinations::Combinations;
+
+  #[test]
+  fn it_returns_c_36_35_is_36() {                                   // 6 x 6 grid
+    let combinations = Combinations::new(36);
+    let from: Vec<usize> = (0..36).collect();
+    let actual = combinations.select(&from, 35);
+
+    assert_eq!(actual.len(), 36);
+    assert_eq!(actual[0].len(), 35);
+    assert_eq!(*actual[0][34], 34);                                 // First combination excludes the last item
+    assert_eq!(*actual[35][0], 1);                                  // Last combination excludes the first item
+  }
+
+  #[test]
+  fn it_only_selects_from_the_max_items() {
+    let combinations = Combinations::new(2);
+    let from = vec![1, 2, 3];
+
+    assert_eq!(combinations.select(&from, 2), vec![vec![&1, &2]]);
+  }
+
+  #[test]
+  fn it_counts_the_combinations_without_selecting_them() {
+    let combinations = Combinations::new(100);
+
+    assert_eq!(combinations.count(4, 2), 6);
+    assert_eq!(combinations.count(36, 35), combinations.select(&(0..36).collect::<Vec<usize>>(), 35).len() as u128);
+    assert_eq!(combinations.count(100, 50), 100891344545564193334812497256);  // Beyond u64
+    assert_eq!(combinations.count(2, 3), 0);
+  }
+}
+
+#[cfg(test)]
+mod select_beyond_64 {
+  use crate::utils::combinations::Combinations;
+
+  #[test]
+  fn it_returns_c_100_1_is_100() {                                  // Options of a 10 x 10 grid
+    let combinations = Combinations::new(100);
+    let from: Vec<usize> = (0..100).collect();
+    let actual = combinations.select(&from, 1);
+
+    assert_eq!(actual.len(), 100);
+    assert_eq!(actual[99], vec![&99]);
+  }
+}
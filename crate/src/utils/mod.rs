@@ -6,3 +6,6 @@ mod combinations_test;
 
 pub mod array_utils;
 mod array_utils_test;
+
+pub mod random;
+mod random_test;
@@ -0,0 +1,40 @@
+// Seeded pseudo random number generator (xorshift64*) i.e. the same seed always generates the same sequence
+#[derive(Debug, Clone)]
+pub struct Random {
+  state: u64
+}
+
+impl Random {
+  pub fn new(seed: u64) -> Self {
+    // SplitMix64 to spread similar seeds, ensuring the state is never 0
+    let mut state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    state ^= state >> 31;
+
+    Self { state: if state == 0 { 0x9E37_79B9_7F4A_7C15 } else { state } }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state ^= self.state >> 12;
+    self.state ^= self.state << 25;
+    self.state ^= self.state >> 27;
+
+    self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+  }
+
+  // Random number from 0 up to (excluding) max
+  pub fn below(&mut self, max: usize) -> usize {
+    (self.next_u64() % max as u64) as usize
+  }
+
+  // Fisher-Yates shuffle
+  pub fn shuffle<T>(&mut self, items: &mut [T]) {
+    let mut index = items.len();
+    while index > 1 {
+      index -= 1;
+      let swop = self.below(index + 1);
+      items.swap(index, swop);
+    }
+  }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod random {
+  use crate::utils::random::Random;
+
+  #[test]
+  fn it_repeats_the_sequence_for_the_same_seed() {
+    let mut random = Random::new(42);
+    let mut repeat = Random::new(42);
+
+    for _ in 0..10 {
+      assert_eq!(random.next_u64(), repeat.next_u64());
+    }
+  }
+
+  #[test]
+  fn it_generates_different_sequences_for_different_seeds() {
+    let mut random = Random::new(0);
+    let mut other = Random::new(1);
+
+    assert_ne!(random.next_u64(), other.next_u64());
+  }
+
+  #[test]
+  fn it_returns_numbers_below_max() {
+    let mut random = Random::new(7);
+
+    for _ in 0..100 {
+      assert!(random.below(9) < 9);
+    }
+  }
+
+  #[test]
+  fn it_shuffles_all_items() {
+    let mut random = Random::new(7);
+    let mut items: Vec<usize> = (0..9).collect();
+
+    random.shuffle(&mut items);
+    assert_ne!(items, (0..9).collect::<Vec<usize>>());
+
+    items.sort();
+    assert_eq!(items, (0..9).collect::<Vec<usize>>());
+  }
+}